@@ -42,7 +42,7 @@ void UpdateInterOperators(alkaidsd::AlkaidConfig &config, rust::Vec<rust::Str> &
         }
         else
         {
-            throw std::invalid_argument("Invalid inter operator.");
+            throw std::invalid_argument("Invalid inter operator: " + std::string(arg));
         }
     }
 }
@@ -69,7 +69,7 @@ void UpdateIntraOperators(alkaidsd::AlkaidConfig &config, rust::Vec<rust::Str> &
         }
         else
         {
-            throw std::invalid_argument("Invalid intra operator.");
+            throw std::invalid_argument("Invalid intra operator: " + std::string(arg));
         }
     }
 }
@@ -143,35 +143,104 @@ void UpdateSorter(alkaidsd::AlkaidConfig &config, rust::Vec<rust::Str> &sorters,
         }
         else
         {
-            throw std::invalid_argument("Invalid sort function.");
+            throw std::invalid_argument("Invalid sort function: " + std::string(sorters[i]));
         }
     }
 }
 
-class SimpleListener : public alkaidsd::Listener
+rust::Vec<int> EncodeRoutes(const alkaidsd::AlkaidSolution &solution)
+{
+    rust::Vec<int> routes;
+    for (alkaidsd::Node node_index : solution.NodeIndices())
+    {
+        if (!solution.Predecessor(node_index))
+        {
+            routes.push_back(0);
+            while (node_index)
+            {
+                alkaidsd::Node customer = solution.Customer(node_index);
+                routes.push_back(customer);
+                routes.push_back(solution.Load(node_index));
+                node_index = solution.Successor(node_index);
+            }
+            routes.push_back(0);
+        }
+    }
+    return routes;
+}
+
+class RustListener : public alkaidsd::Listener
 {
 public:
-    void OnStart() override { start_time_ = std::chrono::system_clock::now(); }
-    void OnUpdated([[maybe_unused]] const alkaidsd::AlkaidSolution &solution, int objective) override
+    RustListener(alkaidsd::ListenerAdapter &adapter,
+                 alkaidsd::DistanceMatrixOptimizer &distance_matrix_optimizer)
+        : adapter_(adapter), distance_matrix_optimizer_(distance_matrix_optimizer) {}
+    void OnStart() override { adapter_.on_start(); }
+    void OnUpdated(const alkaidsd::AlkaidSolution &solution, int objective) override
     {
-        auto elapsed_time = std::chrono::duration_cast<std::chrono::duration<double>>(
-            std::chrono::system_clock::now() - start_time_);
-        std::cout << "Update at " << elapsed_time.count() << "s: " << objective << std::endl;
+        auto restored = solution;
+        distance_matrix_optimizer_.Restore(restored);
+        adapter_.on_updated(EncodeRoutes(restored), objective);
+        best_ = std::make_unique<alkaidsd::AlkaidSolution>(std::move(restored));
     }
-    void OnEnd([[maybe_unused]] const alkaidsd::AlkaidSolution &solution, int objective) override
+    // The end is reported from Rust, with the solution after post-processing.
+    void OnEnd(const alkaidsd::AlkaidSolution &, int) override {}
+
+    bool HasSolution() const { return best_ != nullptr; }
+
+    // Ends a search that was stopped by a `StopSearch`, with the best solution reported so far.
+    rust::Vec<int> EndStopped() { return EncodeRoutes(*best_); }
+
+private:
+    alkaidsd::ListenerAdapter &adapter_;
+    alkaidsd::DistanceMatrixOptimizer &distance_matrix_optimizer_;
+    std::unique_ptr<alkaidsd::AlkaidSolution> best_;
+};
+
+// Thrown out of the search to end it, caught by `solve_sdvrp`.
+struct StopSearch
+{
+};
+
+// The solver has no stopping hook other than its time limit. This acceptance rule is consulted
+// once per iteration, so it polls the Rust side there and throws `StopSearch` when the search
+// should end early, which leaves the solver loop at once instead of waiting for it to look at
+// the time limit again. Without a time limit, the progress is measured in iterations instead,
+// which keeps the search independent of the wall-clock time.
+class StoppableAcceptanceRule : public alkaidsd::acceptance_rule::AcceptanceRule
+{
+public:
+    StoppableAcceptanceRule(std::unique_ptr<alkaidsd::acceptance_rule::AcceptanceRule> acceptance_rule,
+                            alkaidsd::ListenerAdapter &adapter)
+        : acceptance_rule_(std::move(acceptance_rule)), adapter_(adapter) {}
+    bool Accept(int reference_objective, int objective, double progress, alkaidsd::Random &random) override
     {
-        auto elapsed_time = std::chrono::duration_cast<std::chrono::duration<double>>(
-            std::chrono::system_clock::now() - start_time_);
-        std::cout << "End at " << elapsed_time.count() << "s: " << objective << std::endl;
+        if (adapter_.should_stop())
+        {
+            throw StopSearch{};
+        }
+        double iteration_progress = adapter_.progress();
+        if (iteration_progress >= 0)
+        {
+            progress = iteration_progress;
+        }
+        return acceptance_rule_->Accept(reference_objective, objective, progress, random);
     }
 
 private:
-    std::chrono::system_clock::time_point start_time_;
+    std::unique_ptr<alkaidsd::acceptance_rule::AcceptanceRule> acceptance_rule_;
+    alkaidsd::ListenerAdapter &adapter_;
 };
 
+void MakeStoppable(alkaidsd::AlkaidConfig &config, alkaidsd::ListenerAdapter &adapter)
+{
+    config.acceptance_rule = ([acceptance_rule = std::move(config.acceptance_rule), &adapter]()
+                              { return std::make_unique<StoppableAcceptanceRule>(acceptance_rule(), adapter); });
+}
+
 namespace alkaidsd
 {
-    rust::Vec<int> solve_sdvrp(
+    SolveResult solve_sdvrp(
         uint32_t random_seed,
         double time_limit,
         double blink_rate,
@@ -192,10 +261,8 @@ namespace alkaidsd
 
         int capacity,
         rust::Vec<int> demands,
-        rust::Str input_format,
         rust::Vec<int> distance_matrix,
-        rust::Vec<int> coord_list_x,
-        rust::Vec<int> coord_list_y)
+        ListenerAdapter &listener)
     {
         AlkaidConfig config;
         config.random_seed = random_seed;
@@ -207,7 +274,6 @@ namespace alkaidsd
         UpdateRuinMethod(config, ruin_method_type, sisrs_average_customers, sisrs_max_length,
                          sisrs_split_rate, sisrs_preserved_probability, random_ruin_sizes);
         UpdateSorter(config, sorters, sorter_values);
-        config.listener = std::make_unique<SimpleListener>();
 
         alkaidsd::Instance instance;
         instance.num_customers = demands.size() + 1; // +1 for depot
@@ -217,57 +283,38 @@ namespace alkaidsd
         {
             instance.demands[i] = demands[i - 1];
         }
+        // The distances are computed on the Rust side, so that they agree with `Solution::cost`.
         instance.distance_matrix.resize(instance.num_customers);
-        if (input_format == "DENSE_MATRIX")
-        {
-            for (alkaidsd::Node i = 0; i < instance.num_customers; ++i)
-            {
-                instance.distance_matrix[i].resize(instance.num_customers);
-                for (alkaidsd::Node j = 0; j < instance.num_customers; ++j)
-                {
-                    instance.distance_matrix[i][j] = distance_matrix[i * instance.num_customers + j];
-                }
-            }
-        }
-        else if (input_format == "COORD_LIST")
+        for (alkaidsd::Node i = 0; i < instance.num_customers; ++i)
         {
-            for (alkaidsd::Node i = 0; i < instance.num_customers; ++i)
+            instance.distance_matrix[i].resize(instance.num_customers);
+            for (alkaidsd::Node j = 0; j < instance.num_customers; ++j)
             {
-                instance.distance_matrix[i].resize(instance.num_customers);
-                for (alkaidsd::Node j = 0; j < instance.num_customers; ++j)
-                {
-                    auto x1 = coord_list_x[i];
-                    auto y1 = coord_list_y[i];
-                    auto x2 = coord_list_x[j];
-                    auto y2 = coord_list_y[j];
-                    instance.distance_matrix[i][j] = lround(hypot(x1 - x2, y1 - y2));
-                }
+                instance.distance_matrix[i][j] = distance_matrix[i * instance.num_customers + j];
             }
         }
-        else
-        {
-            throw std::invalid_argument("Invalid input_format.");
-        }
 
         auto distance_matrix_optimizer = alkaidsd::DistanceMatrixOptimizer(instance.distance_matrix);
+        auto rust_listener = std::make_unique<RustListener>(listener, distance_matrix_optimizer);
+        auto *listener_ptr = rust_listener.get();
+        config.listener = std::move(rust_listener);
+        MakeStoppable(config, listener);
         alkaidsd::AlkaidSolver solver;
-        auto solution = solver.Solve(config, instance);
-        distance_matrix_optimizer.Restore(solution);
-
-        rust::Vec<int> result;
-        for (Node node_index : solution.NodeIndices())
+        SolveResult result;
+        try
+        {
+            auto solution = solver.Solve(config, instance);
+            distance_matrix_optimizer.Restore(solution);
+            result.found = true;
+            result.routes = EncodeRoutes(solution);
+        }
+        catch (const StopSearch &)
         {
-            if (!solution.Predecessor(node_index))
+            // Stopped before the first solution, there are no routes to return.
+            result.found = listener_ptr->HasSolution();
+            if (result.found)
             {
-                result.push_back(0);
-                while (node_index)
-                {
-                    Node customer = solution.Customer(node_index);
-                    result.push_back(customer);
-                    result.push_back(solution.Load(node_index));
-                    node_index = solution.Successor(node_index);
-                }
-                result.push_back(0);
+                result.routes = listener_ptr->EndStopped();
             }
         }
         return result;
@@ -6,12 +6,14 @@
 #include <iostream>
 #include <fstream>
 #include <cmath>
+#include <memory>
+#include <stdexcept>
 #include "rust/cxx.h"
 #include "sdvrp/src/lib.rs.h"
 
 namespace alkaidsd
 {
-    rust::Vec<int> solve_sdvrp(
+    SolveResult solve_sdvrp(
         uint32_t random_seed,
         double time_limit,
         double blink_rate,
@@ -32,8 +34,6 @@ namespace alkaidsd
 
         int capacity,
         rust::Vec<int> demands,
-        rust::Str input_format,
         rust::Vec<int> distance_matrix,
-        rust::Vec<int> coord_list_x,
-        rust::Vec<int> coord_list_y);
+        ListenerAdapter &listener);
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::listener::SolveListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// a handle to cancel a running solve from another thread.
+///
+/// clones share the same state, pass one clone as (part of) the listener of
+/// `solve_sdvrp_with_listener` and keep another to call [`CancellationToken::cancel`].
+/// the solver polls the token once per iteration and returns the best solution found so far, see
+/// [`SolveListener::should_stop`].
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// requests the solve using this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// whether [`CancellationToken::cancel`] has been called on this token or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl SolveListener for CancellationToken {
+    fn should_stop(&mut self) -> bool {
+        self.is_cancelled()
+    }
+}
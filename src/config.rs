@@ -1,7 +1,8 @@
 #![allow(dead_code)]
 
 /// inter-route operators to be used by the algorithm.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterOperators {
     Swap20,
     Swap21,
@@ -15,6 +16,19 @@ pub enum InterOperators {
 }
 
 impl InterOperators {
+    /// every inter-route operator.
+    pub const ALL: [InterOperators; 9] = [
+        InterOperators::Swap20,
+        InterOperators::Swap21,
+        InterOperators::Swap22,
+        InterOperators::Relocate,
+        InterOperators::SwapStar,
+        InterOperators::Cross,
+        InterOperators::SdSwapStar,
+        InterOperators::SdSwapOneOne,
+        InterOperators::SdSwapTwoOne,
+    ];
+
     pub fn to_str(&self) -> &'static str {
         match self {
             InterOperators::Swap20 => "Swap<2, 0>",
@@ -28,10 +42,30 @@ impl InterOperators {
             InterOperators::SdSwapTwoOne => "SdSwapTwoOne",
         }
     }
+
+    /// whether the operator may reverse a segment of a route, which only keeps the cost of the
+    /// segment if the distances are symmetric. such operators are left out of the search of an
+    /// instance with asymmetric distances.
+    ///
+    /// the classification follows the moves the operators are named after: the swaps of a
+    /// segment of two customers and `Cross` may insert a segment in reverse order, and
+    /// `SdSwapTwoOne` swaps such a segment while splitting a demand. the Alkaid-SDVRP sources are
+    /// not part of this crate, so it is not checked against their implementation.
+    pub fn assumes_symmetry(&self) -> bool {
+        matches!(
+            self,
+            InterOperators::Swap20
+                | InterOperators::Swap21
+                | InterOperators::Swap22
+                | InterOperators::Cross
+                | InterOperators::SdSwapTwoOne
+        )
+    }
 }
 
 /// intra-route operators to be used by the algorithm.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntraOperators {
     Exchange,
     OrOpt1,
@@ -40,6 +74,14 @@ pub enum IntraOperators {
 }
 
 impl IntraOperators {
+    /// every intra-route operator.
+    pub const ALL: [IntraOperators; 4] = [
+        IntraOperators::Exchange,
+        IntraOperators::OrOpt1,
+        IntraOperators::OrOpt2,
+        IntraOperators::OrOpt3,
+    ];
+
     pub fn to_str(&self) -> &'static str {
         match self {
             IntraOperators::Exchange => "Exchange",
@@ -48,10 +90,22 @@ impl IntraOperators {
             IntraOperators::OrOpt3 => "OrOpt<3>",
         }
     }
+
+    /// whether the operator may reverse a segment of a route, see
+    /// [`InterOperators::assumes_symmetry`]: `OrOpt<2>` and `OrOpt<3>` may reinsert their segment
+    /// in reverse order.
+    pub fn assumes_symmetry(&self) -> bool {
+        matches!(self, IntraOperators::OrOpt2 | IntraOperators::OrOpt3)
+    }
 }
 
 /// the type and arguments of acceptance rule to be used by the algorithm.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "AcceptanceRuleRepr", into = "AcceptanceRuleRepr")
+)]
 pub enum AcceptanceRuleType {
     /// Hill Climbing
     HC,
@@ -105,7 +159,12 @@ impl AcceptanceRuleType {
 }
 
 /// the type and arguments of ruin method to be used by the algorithm.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "RuinMethodRepr", into = "RuinMethodRepr")
+)]
 pub enum RuinMethodType {
     /// Slack Induction by String Removals
     /// - average_customers: the average number of customers to be removed.
@@ -168,8 +227,110 @@ impl RuinMethodType {
     }
 }
 
+/// the serialized form of [`AcceptanceRuleType`], which names the arguments of every rule.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+enum AcceptanceRuleRepr {
+    HC,
+    HCWE,
+    LAHC {
+        length: i32,
+    },
+    SA {
+        initial_temperature: f64,
+        decay: f64,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl From<AcceptanceRuleRepr> for AcceptanceRuleType {
+    fn from(repr: AcceptanceRuleRepr) -> Self {
+        match repr {
+            AcceptanceRuleRepr::HC => AcceptanceRuleType::HC,
+            AcceptanceRuleRepr::HCWE => AcceptanceRuleType::HCWE,
+            AcceptanceRuleRepr::LAHC { length } => AcceptanceRuleType::LAHC(length),
+            AcceptanceRuleRepr::SA {
+                initial_temperature,
+                decay,
+            } => AcceptanceRuleType::SA(initial_temperature, decay),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<AcceptanceRuleType> for AcceptanceRuleRepr {
+    fn from(rule: AcceptanceRuleType) -> Self {
+        match rule {
+            AcceptanceRuleType::HC => AcceptanceRuleRepr::HC,
+            AcceptanceRuleType::HCWE => AcceptanceRuleRepr::HCWE,
+            AcceptanceRuleType::LAHC(length) => AcceptanceRuleRepr::LAHC { length },
+            AcceptanceRuleType::SA(initial_temperature, decay) => AcceptanceRuleRepr::SA {
+                initial_temperature,
+                decay,
+            },
+        }
+    }
+}
+
+/// the serialized form of [`RuinMethodType`], which names the arguments of every method.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+enum RuinMethodRepr {
+    SISRs {
+        average_customers: i32,
+        max_length: i32,
+        split_rate: f64,
+        preserved_probability: f64,
+    },
+    Random {
+        sizes: Vec<i32>,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl From<RuinMethodRepr> for RuinMethodType {
+    fn from(repr: RuinMethodRepr) -> Self {
+        match repr {
+            RuinMethodRepr::SISRs {
+                average_customers,
+                max_length,
+                split_rate,
+                preserved_probability,
+            } => RuinMethodType::SISRs(
+                average_customers,
+                max_length,
+                split_rate,
+                preserved_probability,
+            ),
+            RuinMethodRepr::Random { sizes } => RuinMethodType::Random(sizes),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<RuinMethodType> for RuinMethodRepr {
+    fn from(method: RuinMethodType) -> Self {
+        match method {
+            RuinMethodType::SISRs(
+                average_customers,
+                max_length,
+                split_rate,
+                preserved_probability,
+            ) => RuinMethodRepr::SISRs {
+                average_customers,
+                max_length,
+                split_rate,
+                preserved_probability,
+            },
+            RuinMethodType::Random(sizes) => RuinMethodRepr::Random { sizes },
+        }
+    }
+}
+
 /// sorter to be used by the perturbation process.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sorter {
     /// randomly shuffles customers.
     Random,
@@ -182,6 +343,9 @@ pub enum Sorter {
 }
 
 impl Sorter {
+    /// every sorter.
+    pub const ALL: [Sorter; 4] = [Sorter::Random, Sorter::Demand, Sorter::Far, Sorter::Close];
+
     pub fn to_str(&self) -> &'static str {
         match self {
             Sorter::Random => "random",
@@ -192,10 +356,36 @@ impl Sorter {
     }
 }
 
+/// additional criteria that end the search before `time_limit` expires.
+///
+/// every criterion is optional and the search ends as soon as any of the set ones is met.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Termination {
+    /// the maximum number of iterations of the search.
+    pub max_iterations: Option<u64>,
+    /// ends the search once a solution with an objective value at most this value is found.
+    pub target_objective: Option<i32>,
+    /// the maximum number of consecutive iterations without improving the best solution.
+    pub max_stagnation: Option<u64>,
+}
+
+impl Termination {
+    /// whether the search is guaranteed to end without a time limit.
+    pub fn is_bounded(&self) -> bool {
+        self.max_iterations.is_some() || self.max_stagnation.is_some()
+    }
+}
+
 pub trait AlkaidConfig {
     /// the seed value for the random number generator used by the algorithm.
     fn random_seed(&self) -> u32;
-    /// the maximum time limit (in seconds) for the algorithm to run.
+    /// the maximum time limit (in seconds) for the algorithm to run,
+    /// may be infinite if `termination` bounds the number of iterations.
     fn time_limit(&self) -> f64;
     /// the blink rate for the SplitReinsertion process.
     fn blink_rate(&self) -> f64;
@@ -209,12 +399,23 @@ pub trait AlkaidConfig {
     fn ruin_method_type(&self) -> &RuinMethodType;
     /// the list of sorters to be used by the perturbation process.
     fn sorters(&self) -> &[(Sorter, f64)];
+    /// additional criteria that end the search before `time_limit` expires.
+    fn termination(&self) -> Termination {
+        Termination::default()
+    }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct Config {
     /// the seed value for the random number generator used by the algorithm.
     pub random_seed: u32,
-    /// the maximum time limit (in seconds) for the algorithm to run.
+    /// the maximum time limit (in seconds) for the algorithm to run,
+    /// may be infinite if `termination` bounds the number of iterations.
     pub time_limit: f64,
     /// the blink rate for the SplitReinsertion process.
     pub blink_rate: f64,
@@ -223,11 +424,15 @@ pub struct Config {
     /// the list of intra-route operators to be used by the algorithm.
     pub intra_operators: Vec<IntraOperators>,
     /// the type and arguments of acceptance rule to be used by the algorithm.
+    #[cfg_attr(feature = "serde", serde(rename = "acceptance"))]
     pub acceptance_rule_type: AcceptanceRuleType,
     /// the type and arguments of ruin method to be used by the algorithm.
+    #[cfg_attr(feature = "serde", serde(rename = "ruin_method"))]
     pub ruin_method_type: RuinMethodType,
     /// the list of sorters to be used by the perturbation process.
     pub sorters: Vec<(Sorter, f64)>,
+    /// additional criteria that end the search before `time_limit` expires.
+    pub termination: Termination,
 }
 
 impl Default for Config {
@@ -254,6 +459,23 @@ impl Default for Config {
                 (Sorter::Far, 0.942),
                 (Sorter::Close, 0.120),
             ],
+            termination: Termination::default(),
+        }
+    }
+}
+
+impl Config {
+    /// a configuration whose search is bounded only by `max_iterations`, so that solving
+    /// the same instance with the same `random_seed` always yields the same routes,
+    /// regardless of the speed or load of the machine.
+    pub fn deterministic(max_iterations: u64) -> Self {
+        Self {
+            time_limit: f64::INFINITY,
+            termination: Termination {
+                max_iterations: Some(max_iterations),
+                ..Default::default()
+            },
+            ..Default::default()
         }
     }
 }
@@ -290,4 +512,264 @@ impl AlkaidConfig for Config {
     fn sorters(&self) -> &[(Sorter, f64)] {
         &self.sorters
     }
+
+    fn termination(&self) -> Termination {
+        self.termination
+    }
+}
+
+/// checks that every value of `config` is in the range accepted by the solver.
+pub fn validate<T: AlkaidConfig + ?Sized>(config: &T) -> Result<(), String> {
+    if config.time_limit().is_nan() || config.time_limit() <= 0.0 {
+        return Err(format!(
+            "time_limit must be positive, got {}",
+            config.time_limit()
+        ));
+    }
+    let termination = config.termination();
+    if config.time_limit().is_infinite() && !termination.is_bounded() {
+        return Err(
+            "time_limit may only be infinite if max_iterations or max_stagnation is set".to_owned(),
+        );
+    }
+    if termination.max_iterations == Some(0) {
+        return Err("max_iterations must be positive".to_owned());
+    }
+    if termination.max_stagnation == Some(0) {
+        return Err("max_stagnation must be positive".to_owned());
+    }
+    if !(0.0..=1.0).contains(&config.blink_rate()) {
+        return Err(format!(
+            "blink_rate must be in [0, 1], got {}",
+            config.blink_rate()
+        ));
+    }
+    match config.acceptance_rule_type() {
+        AcceptanceRuleType::LAHC(length) if length <= 0 => {
+            return Err(format!("LAHC length must be positive, got {}", length));
+        }
+        AcceptanceRuleType::SA(initial_temperature, decay) => {
+            if !(initial_temperature >= 0.0 && initial_temperature.is_finite()) {
+                return Err(format!(
+                    "SA initial_temperature must be non-negative and finite, got {}",
+                    initial_temperature
+                ));
+            }
+            if !(decay > 0.0 && decay <= 1.0) {
+                return Err(format!("SA decay must be in (0, 1], got {}", decay));
+            }
+        }
+        _ => {}
+    }
+    match config.ruin_method_type() {
+        RuinMethodType::SISRs(average_customers, max_length, split_rate, preserved_probability) => {
+            if *average_customers <= 0 {
+                return Err(format!(
+                    "SISRs average_customers must be positive, got {}",
+                    average_customers
+                ));
+            }
+            if *max_length <= 0 {
+                return Err(format!(
+                    "SISRs max_length must be positive, got {}",
+                    max_length
+                ));
+            }
+            if !(0.0..=1.0).contains(split_rate) {
+                return Err(format!(
+                    "SISRs split_rate must be in [0, 1], got {}",
+                    split_rate
+                ));
+            }
+            if !(0.0..=1.0).contains(preserved_probability) {
+                return Err(format!(
+                    "SISRs preserved_probability must be in [0, 1], got {}",
+                    preserved_probability
+                ));
+            }
+        }
+        RuinMethodType::Random(sizes) => {
+            if sizes.is_empty() {
+                return Err("Random ruin sizes must not be empty".to_owned());
+            }
+            if let Some(size) = sizes.iter().find(|&&size| size <= 0) {
+                return Err(format!("Random ruin sizes must be positive, got {}", size));
+            }
+        }
+    }
+    if config.sorters().is_empty() {
+        return Err("at least one sorter is required".to_owned());
+    }
+    if let Some((sorter, value)) = config
+        .sorters()
+        .iter()
+        .find(|(_, value)| !(*value >= 0.0 && value.is_finite()))
+    {
+        return Err(format!(
+            "sorter {} must have a non-negative finite weight, got {}",
+            sorter.to_str(),
+            value
+        ));
+    }
+    Ok(())
+}
+
+/// checks that `config` only uses operators that are correct on an instance whose distances are
+/// not symmetric, see [`InterOperators::assumes_symmetry`]. the solve functions do not require
+/// this, they leave the other operators out of the search of such an instance, and return
+/// [`SolveError::InvalidConfig`](crate::SolveError::InvalidConfig) if that leaves no inter or no
+/// intra operator.
+pub fn validate_asymmetric<T: AlkaidConfig + ?Sized>(config: &T) -> Result<(), String> {
+    if let Some(operator) = config
+        .inter_operators()
+        .iter()
+        .find(|operator| operator.assumes_symmetry())
+    {
+        return Err(format!(
+            "inter operator {:?} requires symmetric distances",
+            operator
+        ));
+    }
+    if let Some(operator) = config
+        .intra_operators()
+        .iter()
+        .find(|operator| operator.assumes_symmetry())
+    {
+        return Err(format!(
+            "intra operator {:?} requires symmetric distances",
+            operator
+        ));
+    }
+    Ok(())
+}
+
+/// `config` with the time limit and operators of a single search, which the solve functions adapt
+/// to the instance.
+pub(crate) struct Adjusted<'a, T: ?Sized> {
+    config: &'a T,
+    pub(crate) time_limit: f64,
+    pub(crate) inter_operators: Vec<InterOperators>,
+    pub(crate) intra_operators: Vec<IntraOperators>,
+}
+
+impl<'a, T: AlkaidConfig + ?Sized> Adjusted<'a, T> {
+    pub(crate) fn new(config: &'a T) -> Self {
+        Self {
+            config,
+            time_limit: config.time_limit(),
+            inter_operators: config.inter_operators().to_vec(),
+            intra_operators: config.intra_operators().to_vec(),
+        }
+    }
+
+    /// leaves out the operators that assume symmetric distances, see
+    /// [`InterOperators::assumes_symmetry`]. fails if that leaves no inter or no intra operator
+    /// of a non-empty list, rather than searching without the operators the config asks for.
+    pub(crate) fn without_symmetric_operators(mut self) -> Result<Self, String> {
+        if !self.inter_operators.is_empty()
+            && self
+                .inter_operators
+                .iter()
+                .all(|operator| operator.assumes_symmetry())
+        {
+            return Err(format!(
+                "inter operators {:?} all require symmetric distances",
+                self.inter_operators
+            ));
+        }
+        if !self.intra_operators.is_empty()
+            && self
+                .intra_operators
+                .iter()
+                .all(|operator| operator.assumes_symmetry())
+        {
+            return Err(format!(
+                "intra operators {:?} all require symmetric distances",
+                self.intra_operators
+            ));
+        }
+        self.inter_operators
+            .retain(|operator| !operator.assumes_symmetry());
+        self.intra_operators
+            .retain(|operator| !operator.assumes_symmetry());
+        Ok(self)
+    }
+}
+
+impl<T: AlkaidConfig + ?Sized> AlkaidConfig for Adjusted<'_, T> {
+    fn random_seed(&self) -> u32 {
+        self.config.random_seed()
+    }
+
+    fn time_limit(&self) -> f64 {
+        self.time_limit
+    }
+
+    fn blink_rate(&self) -> f64 {
+        self.config.blink_rate()
+    }
+
+    fn inter_operators(&self) -> &[InterOperators] {
+        &self.inter_operators
+    }
+
+    fn intra_operators(&self) -> &[IntraOperators] {
+        &self.intra_operators
+    }
+
+    fn acceptance_rule_type(&self) -> AcceptanceRuleType {
+        self.config.acceptance_rule_type()
+    }
+
+    fn ruin_method_type(&self) -> &RuinMethodType {
+        self.config.ruin_method_type()
+    }
+
+    fn sorters(&self) -> &[(Sorter, f64)] {
+        self.config.sorters()
+    }
+
+    fn termination(&self) -> Termination {
+        self.config.termination()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_symmetric_operators() {
+        let config = Config::default();
+        let adjusted = Adjusted::new(&config)
+            .without_symmetric_operators()
+            .unwrap();
+        assert!(
+            adjusted
+                .inter_operators
+                .iter()
+                .all(|operator| !operator.assumes_symmetry())
+        );
+        assert!(!adjusted.inter_operators.is_empty());
+        assert!(!adjusted.intra_operators.is_empty());
+
+        let config = Config {
+            inter_operators: vec![InterOperators::Swap20, InterOperators::Cross],
+            ..Default::default()
+        };
+        assert!(
+            Adjusted::new(&config)
+                .without_symmetric_operators()
+                .is_err()
+        );
+        let config = Config {
+            intra_operators: vec![IntraOperators::OrOpt2],
+            ..Default::default()
+        };
+        assert!(
+            Adjusted::new(&config)
+                .without_symmetric_operators()
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,519 @@
+use crate::error::{InstanceError, SolveError};
+use crate::feasibility::{Violation, check_feasibility};
+use crate::instance::{InputFormat, Instance, validate_matrix_shape};
+use crate::listener::SolveListener;
+use crate::solution::{Route, Solution};
+
+/// a depot of a [`MultiDepotInstance`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Depot {
+    /// the number of vehicles based at the depot, or `None` if its fleet is unlimited.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub max_vehicles: Option<usize>,
+}
+
+/// an instance with several depots, where every route starts and ends at the same depot.
+///
+/// the first `depots.len()` nodes of the input are the depots, customer `i` is node
+/// `depots.len() + i - 1`. customers keep their numbers in the routes of a solution, whose
+/// [`depot`](crate::Route::depot) tells the depot of every route.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawMultiDepotInstance")
+)]
+pub struct MultiDepotInstance {
+    capacity: i32,
+    demands: Vec<i32>,
+    depots: Vec<Depot>,
+    input: InputFormat,
+}
+
+impl MultiDepotInstance {
+    /// creates an instance.
+    ///
+    /// # Panics
+    /// panics if the data is rejected by [`MultiDepotInstance::try_new`].
+    pub fn new(capacity: i32, demands: Vec<i32>, depots: Vec<Depot>, input: InputFormat) -> Self {
+        Self::try_new(capacity, demands, depots, input).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// creates an instance, returning the first rule violated by the data.
+    pub fn try_new(
+        capacity: i32,
+        demands: Vec<i32>,
+        depots: Vec<Depot>,
+        input: InputFormat,
+    ) -> Result<Self, InstanceError> {
+        let instance = Self {
+            capacity,
+            demands,
+            depots,
+            input,
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    pub fn capacity(&self) -> i32 {
+        self.capacity
+    }
+
+    pub fn demands(&self) -> &[i32] {
+        &self.demands
+    }
+
+    pub fn depots(&self) -> &[Depot] {
+        &self.depots
+    }
+
+    pub fn input_format(&self) -> &InputFormat {
+        &self.input
+    }
+
+    /// the node of customer `customer` in the input.
+    fn node(&self, customer: usize) -> usize {
+        self.depots.len() + customer - 1
+    }
+
+    /// the single-depot instance seen from `depot`, with every customer under its own number and
+    /// without the fleet limit of the depot. the routes of `depot` have the same distance in
+    /// both.
+    ///
+    /// # Panics
+    /// panics if `depot` is not the index of a depot. the instance is valid as seen from every
+    /// depot, see [`MultiDepotInstance::try_new`], so it panics on nothing else.
+    pub fn depot_instance(&self, depot: usize) -> Instance {
+        assert!(
+            depot < self.depots.len(),
+            "depot {} out of range for {} depots",
+            depot,
+            self.depots.len()
+        );
+        self.sub_instance(depot, &self.whole_demands())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// every customer with its whole demand.
+    fn whole_demands(&self) -> Vec<(usize, i32)> {
+        (1..=self.demands.len())
+            .map(|customer| (customer, self.demands[customer - 1]))
+            .collect()
+    }
+
+    /// the single-depot instance of `depot` delivering `parts`, where customer `i` of the result
+    /// is customer `parts[i - 1].0` with the demand `parts[i - 1].1`.
+    pub(crate) fn sub_instance(
+        &self,
+        depot: usize,
+        parts: &[(usize, i32)],
+    ) -> Result<Instance, InstanceError> {
+        let nodes = std::iter::once(depot)
+            .chain(parts.iter().map(|&(customer, _)| self.node(customer)))
+            .collect::<Vec<_>>();
+        let demands = parts.iter().map(|&(_, quantity)| quantity).collect();
+        Instance::try_new(self.capacity, demands, self.input.select(&nodes))
+    }
+
+    /// assigns the demand of every customer to the depots: customers with larger demands first,
+    /// each to the depot with the shortest round trip whose fleet still has room. a demand that
+    /// exceeds the room left at that depot is split, and the rest goes to the next depots by round
+    /// trip, or to the nearest depot if no depot has room left.
+    ///
+    /// returns the customers of every depot in increasing order, each with the quantity the depot
+    /// delivers to it.
+    pub fn assign_customers(&self) -> Vec<Vec<(usize, i32)>> {
+        let round_trip = |depot: usize, customer: usize| {
+            let node = self.node(customer);
+            self.input.distance(depot, node) as i64 + self.input.distance(node, depot) as i64
+        };
+        let mut room = self
+            .depots
+            .iter()
+            .map(|depot| {
+                depot.max_vehicles.map_or(i64::MAX, |max_vehicles| {
+                    max_vehicles as i64 * self.capacity as i64
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut customers = (1..=self.demands.len()).collect::<Vec<_>>();
+        customers.sort_by_key(|&customer| std::cmp::Reverse(self.demands[customer - 1]));
+        let mut assigned = vec![vec![]; self.depots.len()];
+        for customer in customers {
+            let mut depots = (0..self.depots.len()).collect::<Vec<_>>();
+            depots.sort_by_key(|&depot| round_trip(depot, customer));
+            let mut rest = self.demands[customer - 1];
+            for &depot in &depots {
+                let quantity = room[depot].min(rest as i64) as i32;
+                if quantity > 0 {
+                    room[depot] -= quantity as i64;
+                    assigned[depot].push((customer, quantity));
+                    rest -= quantity;
+                }
+            }
+            if rest > 0 {
+                let nearest = depots[0];
+                match assigned[nearest]
+                    .iter_mut()
+                    .find(|&&mut (c, _)| c == customer)
+                {
+                    Some((_, quantity)) => *quantity += rest,
+                    None => assigned[nearest].push((customer, rest)),
+                }
+            }
+        }
+        for parts in &mut assigned {
+            parts.sort_unstable();
+        }
+        assigned
+    }
+
+    /// checks the routes of `solution` against the instance: every route against the
+    /// [`depot_instance`](MultiDepotInstance::depot_instance) of its depot and the fleet limit of
+    /// the depot, and the deliveries of all routes against the demands.
+    pub fn violations(&self, solution: &Solution) -> Vec<Violation> {
+        let mut violations = vec![];
+        for (route, depot) in solution.routes.iter().map(|route| route.depot).enumerate() {
+            if depot.is_none_or(|depot| depot >= self.depots.len()) {
+                violations.push(Violation::DepotOutOfRange { route, depot });
+            }
+        }
+        let routes = solution.to_routes();
+        for (depot, limit) in self.depots.iter().enumerate() {
+            // routes of other depots stay in place as empty routes to keep the route indices.
+            let own = solution
+                .routes
+                .iter()
+                .zip(&routes)
+                .map(|(route, visits)| {
+                    if route.depot == Some(depot) {
+                        visits.clone()
+                    } else {
+                        vec![]
+                    }
+                })
+                .collect::<Vec<_>>();
+            violations.extend(
+                check_feasibility(&self.depot_instance(depot), &own)
+                    .into_iter()
+                    .filter(|violation| !is_delivery(violation)),
+            );
+            let vehicles = own.iter().filter(|visits| !visits.is_empty()).count();
+            if let Some(max_vehicles) = limit.max_vehicles
+                && vehicles > max_vehicles
+            {
+                violations.push(Violation::FleetExceeded {
+                    vehicles,
+                    max_vehicles,
+                });
+            }
+        }
+        violations.extend(
+            check_feasibility(&self.depot_instance(0), &routes)
+                .into_iter()
+                .filter(is_delivery),
+        );
+        violations
+    }
+}
+
+/// forwards the search of the [`sub_instance`](MultiDepotInstance::sub_instance) of `depot`
+/// delivering `parts` to `listener`, with the solutions mapped back to the instance, see
+/// [`DepotListener::solution`].
+pub(crate) struct DepotListener<'a, L: ?Sized> {
+    listener: &'a mut L,
+    depot: usize,
+    parts: &'a [(usize, i32)],
+    depot_instance: Instance,
+}
+
+impl<'a, L: SolveListener + ?Sized> DepotListener<'a, L> {
+    pub(crate) fn new(
+        instance: &MultiDepotInstance,
+        depot: usize,
+        parts: &'a [(usize, i32)],
+        listener: &'a mut L,
+    ) -> Self {
+        Self {
+            listener,
+            depot,
+            parts,
+            depot_instance: instance.depot_instance(depot),
+        }
+    }
+
+    /// `solution` of the sub-instance with the customer numbers of the instance and the routes
+    /// carrying their depot.
+    pub(crate) fn solution(&self, solution: &Solution) -> Solution {
+        let routes = solution
+            .routes
+            .iter()
+            .map(|route| {
+                let visits = route
+                    .visits
+                    .iter()
+                    .map(|&(customer, load)| (self.parts[customer as usize - 1].0 as i32, load))
+                    .collect();
+                Route {
+                    depot: Some(self.depot),
+                    vehicle_type: route.vehicle_type,
+                    ..Route::new(&self.depot_instance, visits)
+                }
+            })
+            .collect();
+        Solution {
+            routes,
+            objective: solution.objective,
+        }
+    }
+
+    /// `error` of the search of the sub-instance, with a solution it carries mapped back to the
+    /// instance.
+    pub(crate) fn error(&self, error: SolveError) -> SolveError {
+        match error {
+            SolveError::FleetExceeded {
+                vehicles,
+                max_vehicles,
+                solution,
+            } => SolveError::FleetExceeded {
+                vehicles,
+                max_vehicles,
+                solution: Box::new(self.solution(&solution)),
+            },
+            error => error,
+        }
+    }
+}
+
+impl<L: SolveListener + ?Sized> SolveListener for DepotListener<'_, L> {
+    fn on_start(&mut self) {
+        self.listener.on_start();
+    }
+
+    fn on_updated(&mut self, solution: &Solution, objective: i32) {
+        let solution = self.solution(solution);
+        self.listener.on_updated(&solution, objective);
+    }
+
+    fn on_end(&mut self, solution: &Solution, objective: i32) {
+        let solution = self.solution(solution);
+        self.listener.on_end(&solution, objective);
+    }
+
+    fn should_stop(&mut self) -> bool {
+        self.listener.should_stop()
+    }
+}
+
+/// whether `violation` concerns the total delivery to a customer rather than a single route.
+fn is_delivery(violation: &Violation) -> bool {
+    matches!(
+        violation,
+        Violation::UnderDelivered { .. } | Violation::OverDelivered { .. }
+    )
+}
+
+/// the deserialized form of [`MultiDepotInstance`], which is validated before it becomes an
+/// instance.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawMultiDepotInstance {
+    capacity: i32,
+    demands: Vec<i32>,
+    depots: Vec<Depot>,
+    input: InputFormat,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawMultiDepotInstance> for MultiDepotInstance {
+    type Error = InstanceError;
+
+    fn try_from(raw: RawMultiDepotInstance) -> Result<Self, Self::Error> {
+        Self::try_new(raw.capacity, raw.demands, raw.depots, raw.input)
+    }
+}
+
+/// checks that there is at least one depot, that the input has a node per depot and customer,
+/// that the instance is valid as seen from every depot and that the fleets of the depots can carry
+/// the total demand.
+fn validate(instance: &MultiDepotInstance) -> Result<(), InstanceError> {
+    if instance.depots.is_empty() {
+        return Err(InstanceError::NoDepot);
+    }
+    let num_nodes = instance.depots.len() + instance.demands.len();
+    if let InputFormat::DenseMatrix(matrix) = &instance.input {
+        validate_matrix_shape(matrix, num_nodes)?;
+    } else if instance.input.num_nodes() != num_nodes {
+        return Err(InstanceError::NodeCountMismatch {
+            expected: num_nodes,
+            found: instance.input.num_nodes(),
+        });
+    }
+    let parts = instance.whole_demands();
+    for depot in 0..instance.depots.len() {
+        instance.sub_instance(depot, &parts)?;
+    }
+    let fleet_capacity = instance
+        .depots
+        .iter()
+        .map(|depot| {
+            depot
+                .max_vehicles
+                .map(|max_vehicles| max_vehicles as i64 * instance.capacity as i64)
+        })
+        .sum::<Option<i64>>();
+    let total_demand = instance.demands.iter().map(|&demand| demand as i64).sum();
+    if let Some(fleet_capacity) = fleet_capacity
+        && total_demand > fleet_capacity
+    {
+        return Err(InstanceError::InsufficientFleet {
+            total_demand,
+            fleet_capacity,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::DistanceRounding;
+
+    fn depots() -> Vec<Depot> {
+        vec![
+            Depot {
+                max_vehicles: Some(1)
+            };
+            2
+        ]
+    }
+
+    fn instance() -> MultiDepotInstance {
+        let coords = vec![
+            (0.0, 0.0),
+            (20.0, 0.0),
+            (2.0, 1.0),
+            (3.0, -1.0),
+            (18.0, 1.0),
+            (19.0, -2.0),
+        ];
+        let input = InputFormat::Planar {
+            coords,
+            rounding: DistanceRounding::Round,
+        };
+        MultiDepotInstance::new(10, vec![4, 5, 6, 3], depots(), input)
+    }
+
+    #[test]
+    fn test_assign_customers() {
+        assert_eq!(
+            instance().assign_customers(),
+            vec![vec![(1, 4), (2, 5)], vec![(3, 6), (4, 3)]]
+        );
+
+        // all customers are nearest to depot 0, whose vehicle only has room for one and a part.
+        let crowded = MultiDepotInstance::new(
+            10,
+            vec![6, 6, 6],
+            depots(),
+            InputFormat::Planar {
+                coords: vec![(0.0, 0.0), (20.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)],
+                rounding: DistanceRounding::Round,
+            },
+        );
+        assert_eq!(
+            crowded.assign_customers(),
+            vec![vec![(1, 6), (2, 4)], vec![(2, 2), (3, 6)]]
+        );
+    }
+
+    #[test]
+    fn test_violations() {
+        let instance = instance();
+        let route = |depot: usize, visits: Vec<(i32, i32)>| Route {
+            depot: Some(depot),
+            ..Route::new(&instance.depot_instance(depot), visits)
+        };
+        let mut solution = Solution {
+            routes: vec![
+                route(0, vec![(1, 4), (2, 5)]),
+                route(1, vec![(3, 6), (4, 3)]),
+            ],
+            objective: 0,
+        };
+        assert_eq!(solution.routes[1].distance, 2 + 3 + 2);
+        assert!(instance.violations(&solution).is_empty());
+        solution.routes[1].depot = None;
+        assert_eq!(
+            instance.violations(&solution),
+            vec![Violation::DepotOutOfRange {
+                route: 1,
+                depot: None
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "depot 2 out of range")]
+    fn test_depot_out_of_range() {
+        instance().depot_instance(2);
+    }
+
+    #[test]
+    fn test_fleet_exceeded() {
+        let instance = instance();
+        let parts = instance.assign_customers().remove(1);
+        let sub_instance = instance.sub_instance(1, &parts).unwrap();
+        // two routes for the single vehicle of depot 1.
+        let solution = Solution::new(&sub_instance, vec![vec![(1, 6)], vec![(2, 3)]], 9);
+
+        #[derive(Default)]
+        struct Updates(Vec<Solution>);
+
+        impl SolveListener for Updates {
+            fn on_updated(&mut self, solution: &Solution, _objective: i32) {
+                self.0.push(solution.clone());
+            }
+        }
+
+        let mut updates = Updates::default();
+        let mut listener = DepotListener::new(&instance, 1, &parts, &mut updates);
+        listener.on_updated(&solution, solution.objective);
+        let error = listener.error(SolveError::FleetExceeded {
+            vehicles: 2,
+            max_vehicles: 1,
+            solution: Box::new(solution),
+        });
+        let SolveError::FleetExceeded { solution, .. } = error else {
+            panic!("expected FleetExceeded, got {:?}", error);
+        };
+        assert_eq!(solution.to_routes(), vec![vec![(3, 6)], vec![(4, 3)]]);
+        assert!(solution.routes.iter().all(|route| route.depot == Some(1)));
+        assert_eq!(solution.objective, 9);
+        assert_eq!(updates.0, vec![*solution.clone()]);
+        assert_eq!(
+            instance.violations(&solution),
+            vec![
+                Violation::FleetExceeded {
+                    vehicles: 2,
+                    max_vehicles: 1
+                },
+                Violation::UnderDelivered {
+                    customer: 1,
+                    delivered: 0,
+                    demand: 4
+                },
+                Violation::UnderDelivered {
+                    customer: 2,
+                    delivered: 0,
+                    demand: 5
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,409 @@
+use crate::feasibility::Violation;
+use crate::solution::Solution;
+use std::fmt;
+
+/// errors that can be returned by `solve_sdvrp`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// the configuration contains an invalid value.
+    InvalidConfig(String),
+    /// the instance data is inconsistent or out of range.
+    InvalidInstance(InstanceError),
+    /// the initial solution violates the constraints of the instance.
+    InvalidInitialSolution(Vec<Violation>),
+    /// the solver did not find a solution within the fleet size of the instance. `solution` is
+    /// the best solution found, which uses `vehicles` vehicles.
+    FleetExceeded {
+        vehicles: usize,
+        max_vehicles: usize,
+        solution: Box<Solution>,
+    },
+    /// the routes found by the solver cannot be served by the vehicle types of the fleet.
+    FleetAssignment,
+    /// the search was stopped, by the listener or a [`Termination`](crate::config::Termination)
+    /// criterion, before it found a solution.
+    Cancelled,
+    /// the solver failed while running.
+    Solver(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::InvalidConfig(msg) => write!(f, "invalid config: {}", msg),
+            SolveError::InvalidInstance(err) => write!(f, "invalid instance: {}", err),
+            SolveError::InvalidInitialSolution(violations) => write!(
+                f,
+                "invalid initial solution: {}",
+                violations
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
+            SolveError::FleetExceeded {
+                vehicles,
+                max_vehicles,
+                ..
+            } => write!(
+                f,
+                "no solution found with at most {} vehicles, the best one uses {}",
+                max_vehicles, vehicles
+            ),
+            SolveError::FleetAssignment => {
+                write!(
+                    f,
+                    "no solution found that the vehicles of the fleet can serve"
+                )
+            }
+            SolveError::Cancelled => write!(f, "the search was stopped before it found a solution"),
+            SolveError::Solver(msg) => write!(f, "solver error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<InstanceError> for SolveError {
+    fn from(e: InstanceError) -> Self {
+        SolveError::InvalidInstance(e)
+    }
+}
+
+impl From<cxx::Exception> for SolveError {
+    fn from(e: cxx::Exception) -> Self {
+        SolveError::Solver(e.what().to_owned())
+    }
+}
+
+/// a rule violated by the data of an instance.
+///
+/// node indices count the depot as node `0`, so customer `i` has its demand at `demands[i - 1]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstanceError {
+    /// the vehicle capacity is zero or negative.
+    NonPositiveCapacity(i32),
+    /// the demand of a customer is zero or negative.
+    NonPositiveDemand { customer: usize, demand: i32 },
+    /// the number of rows or coordinates does not equal the number of customers plus the depot.
+    NodeCountMismatch { expected: usize, found: usize },
+    /// a row of the distance or travel time matrix does not have one column per node.
+    RowLengthMismatch {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// a distance in the matrix is negative.
+    NegativeDistance {
+        from: usize,
+        to: usize,
+        distance: i32,
+    },
+    /// the distance from a node to itself is not zero.
+    NonZeroDiagonal { node: usize, distance: i32 },
+    /// a coordinate of a node is infinite or NaN.
+    NonFiniteCoordinate { node: usize },
+    /// the factor of [`DistanceRounding::Scaled`](crate::instance::DistanceRounding::Scaled) is
+    /// not a positive number.
+    InvalidScale(f64),
+    /// distances of the coordinates, up to `distance` before rounding, exceed `i32::MAX` after
+    /// rounding.
+    DistanceOverflow { distance: f64 },
+    /// a latitude is outside of `[-90, 90]` or a longitude outside of `[-180, 180]`.
+    LatLonOutOfRange {
+        node: usize,
+        latitude: f64,
+        longitude: f64,
+    },
+    /// the total demand exceeds the capacity of the whole fleet.
+    InsufficientFleet {
+        total_demand: i64,
+        fleet_capacity: i64,
+    },
+    /// the fleet has no vehicle types.
+    EmptyFleet,
+    /// the fixed cost of a vehicle type is negative.
+    NegativeFixedCost {
+        vehicle_type: usize,
+        fixed_cost: i32,
+    },
+    /// the distance multiplier of a vehicle type is negative or not finite.
+    InvalidDistanceMultiplier {
+        vehicle_type: usize,
+        distance_multiplier: f64,
+    },
+    /// the capacity of the instance is not the largest capacity of its fleet.
+    FleetCapacityMismatch { capacity: i32, largest: i32 },
+    /// the number of service times does not equal the number of customers.
+    ServiceTimeCountMismatch { expected: usize, found: usize },
+    /// the service time of a customer is negative.
+    NegativeServiceTime { customer: usize, service_time: i32 },
+    /// the maximum distance or duration of a route is zero or negative.
+    NonPositiveRouteLimit(i32),
+    /// the number of maximum visits does not equal the number of customers.
+    MaxVisitsCountMismatch { expected: usize, found: usize },
+    /// the maximum number of visits to a customer is zero.
+    ZeroMaxVisits { customer: usize },
+    /// the number of minimum loads does not equal the number of customers.
+    MinDeliveryCountMismatch { expected: usize, found: usize },
+    /// the minimum load of a visit to a customer is zero or negative.
+    NonPositiveMinDelivery { customer: usize, min_delivery: i32 },
+    /// the number of pickups does not equal the number of customers.
+    PickupCountMismatch { expected: usize, found: usize },
+    /// the pickup of a customer is negative or exceeds the capacity.
+    InvalidPickup { customer: usize, pickup: i32 },
+    /// the demand of a customer cannot be split into its maximum number of visits, each of at
+    /// least its minimum load and at most the capacity. a customer with a pickup allows a single
+    /// visit.
+    UnsplittableDemand { customer: usize },
+    /// the number of unserved penalties does not equal the number of customers.
+    UnservedPenaltyCountMismatch { expected: usize, found: usize },
+    /// the penalty for leaving demand of a customer undelivered is negative.
+    NegativeUnservedPenalty { customer: usize, penalty: i32 },
+    /// a multi-depot instance has no depots.
+    NoDepot,
+    /// a travel time between two nodes is negative.
+    NegativeTravelTime {
+        from: usize,
+        to: usize,
+        travel_time: i32,
+    },
+    /// the number of time windows does not equal the number of customers plus the depot.
+    TimeWindowCountMismatch { expected: usize, found: usize },
+    /// a time window ends before it starts.
+    InvalidTimeWindow {
+        node: usize,
+        earliest: i32,
+        latest: i32,
+    },
+    /// a customer cannot be served within the maximum distance or duration of a route or within
+    /// the time windows, even by a route of its own.
+    UnreachableCustomer { customer: usize },
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstanceError::NonPositiveCapacity(capacity) => {
+                write!(f, "capacity must be positive, got {}", capacity)
+            }
+            InstanceError::NonPositiveDemand { customer, demand } => write!(
+                f,
+                "demand of customer {} must be positive, got {}",
+                customer, demand
+            ),
+            InstanceError::NodeCountMismatch { expected, found } => write!(
+                f,
+                "expected {} nodes (depot and customers), got {}",
+                expected, found
+            ),
+            InstanceError::RowLengthMismatch {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} of distance matrix must have {} columns, got {}",
+                row, expected, found
+            ),
+            InstanceError::NegativeDistance { from, to, distance } => write!(
+                f,
+                "distance from {} to {} must be non-negative, got {}",
+                from, to, distance
+            ),
+            InstanceError::NonZeroDiagonal { node, distance } => write!(
+                f,
+                "distance from {} to itself must be zero, got {}",
+                node, distance
+            ),
+            InstanceError::NonFiniteCoordinate { node } => {
+                write!(f, "coordinates of node {} must be finite", node)
+            }
+            InstanceError::InvalidScale(factor) => {
+                write!(f, "distance scale must be positive, got {}", factor)
+            }
+            InstanceError::DistanceOverflow { distance } => write!(
+                f,
+                "distances up to {} exceed the integer range after rounding",
+                distance
+            ),
+            InstanceError::LatLonOutOfRange {
+                node,
+                latitude,
+                longitude,
+            } => write!(
+                f,
+                "coordinates of node {} must be a valid latitude and longitude, got ({}, {})",
+                node, latitude, longitude
+            ),
+            InstanceError::InsufficientFleet {
+                total_demand,
+                fleet_capacity,
+            } => write!(
+                f,
+                "total demand {} exceeds the capacity {} of the fleet",
+                total_demand, fleet_capacity
+            ),
+            InstanceError::EmptyFleet => write!(f, "fleet must have at least one vehicle type"),
+            InstanceError::NegativeFixedCost {
+                vehicle_type,
+                fixed_cost,
+            } => write!(
+                f,
+                "fixed cost of vehicle type {} must be non-negative, got {}",
+                vehicle_type, fixed_cost
+            ),
+            InstanceError::InvalidDistanceMultiplier {
+                vehicle_type,
+                distance_multiplier,
+            } => write!(
+                f,
+                "distance multiplier of vehicle type {} must be non-negative and finite, got {}",
+                vehicle_type, distance_multiplier
+            ),
+            InstanceError::FleetCapacityMismatch { capacity, largest } => write!(
+                f,
+                "capacity {} must be the largest capacity {} of the fleet",
+                capacity, largest
+            ),
+            InstanceError::ServiceTimeCountMismatch { expected, found } => write!(
+                f,
+                "expected {} service times (one per customer), got {}",
+                expected, found
+            ),
+            InstanceError::NegativeServiceTime {
+                customer,
+                service_time,
+            } => write!(
+                f,
+                "service time of customer {} must be non-negative, got {}",
+                customer, service_time
+            ),
+            InstanceError::NonPositiveRouteLimit(limit) => {
+                write!(f, "route limit must be positive, got {}", limit)
+            }
+            InstanceError::MaxVisitsCountMismatch { expected, found } => write!(
+                f,
+                "expected {} maximum visits (one per customer), got {}",
+                expected, found
+            ),
+            InstanceError::ZeroMaxVisits { customer } => {
+                write!(
+                    f,
+                    "maximum visits of customer {} must be positive",
+                    customer
+                )
+            }
+            InstanceError::MinDeliveryCountMismatch { expected, found } => write!(
+                f,
+                "expected {} minimum deliveries (one per customer), got {}",
+                expected, found
+            ),
+            InstanceError::NonPositiveMinDelivery {
+                customer,
+                min_delivery,
+            } => write!(
+                f,
+                "minimum delivery of customer {} must be positive, got {}",
+                customer, min_delivery
+            ),
+            InstanceError::PickupCountMismatch { expected, found } => write!(
+                f,
+                "expected {} pickups (one per customer), got {}",
+                expected, found
+            ),
+            InstanceError::InvalidPickup { customer, pickup } => write!(
+                f,
+                "pickup of customer {} must be between 0 and the capacity, got {}",
+                customer, pickup
+            ),
+            InstanceError::UnsplittableDemand { customer } => write!(
+                f,
+                "demand of customer {} cannot be split within its maximum visits and minimum delivery",
+                customer
+            ),
+            InstanceError::UnservedPenaltyCountMismatch { expected, found } => write!(
+                f,
+                "expected {} unserved penalties (one per customer), got {}",
+                expected, found
+            ),
+            InstanceError::NegativeUnservedPenalty { customer, penalty } => write!(
+                f,
+                "unserved penalty of customer {} must be non-negative, got {}",
+                customer, penalty
+            ),
+            InstanceError::NoDepot => write!(f, "instance must have at least one depot"),
+            InstanceError::NegativeTravelTime {
+                from,
+                to,
+                travel_time,
+            } => write!(
+                f,
+                "travel time from node {} to node {} must be non-negative, got {}",
+                from, to, travel_time
+            ),
+            InstanceError::TimeWindowCountMismatch { expected, found } => write!(
+                f,
+                "expected {} time windows (one per node), got {}",
+                expected, found
+            ),
+            InstanceError::InvalidTimeWindow {
+                node,
+                earliest,
+                latest,
+            } => write!(
+                f,
+                "time window of node {} ends at {} before it starts at {}",
+                node, latest, earliest
+            ),
+            InstanceError::UnreachableCustomer { customer } => write!(
+                f,
+                "customer {} cannot be served within the route limits or time windows",
+                customer
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InstanceError {}
+
+/// errors that can be returned when reading an instance or solution file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// the file could not be read.
+    Io(String),
+    /// a line of the file is malformed, `line` counts from `1`.
+    Syntax { line: usize, message: String },
+    /// a required keyword or section is missing.
+    Missing(String),
+    /// the file uses a feature that is not supported.
+    Unsupported(String),
+    /// the data of the file does not form a valid instance.
+    Instance(InstanceError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(msg) => write!(f, "cannot read file: {}", msg),
+            ParseError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ParseError::Missing(what) => write!(f, "missing {}", what),
+            ParseError::Unsupported(what) => write!(f, "unsupported {}", what),
+            ParseError::Instance(err) => write!(f, "invalid instance: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<std::io::Error> for ParseError {
+    fn from(e: std::io::Error) -> Self {
+        ParseError::Io(e.to_string())
+    }
+}
+
+impl From<InstanceError> for ParseError {
+    fn from(e: InstanceError) -> Self {
+        ParseError::Instance(e)
+    }
+}
@@ -0,0 +1,594 @@
+use crate::instance::{
+    AlkaidInstance, min_delivery, route_distance, route_peak_load, route_schedule, saturate,
+    visit_limit,
+};
+use std::fmt;
+
+/// a constraint violated by a set of routes.
+///
+/// `route` and `position` index into the checked routes, `customer` and `node` count the depot as `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// the load on board of a route exceeds the vehicle capacity at some point, see
+    /// [`route_peak_load`].
+    CapacityExceeded {
+        route: usize,
+        load: i32,
+        capacity: i32,
+    },
+    /// a customer receives less than its demand, on an instance without
+    /// [`unserved_penalties`](AlkaidInstance::unserved_penalties).
+    UnderDelivered {
+        customer: i32,
+        delivered: i32,
+        demand: i32,
+    },
+    /// a customer receives more than its demand.
+    OverDelivered {
+        customer: i32,
+        delivered: i32,
+        demand: i32,
+    },
+    /// a visit delivers a zero or negative load.
+    NonPositiveLoad {
+        route: usize,
+        position: usize,
+        load: i32,
+    },
+    /// a visit refers to a node that does not exist.
+    NodeOutOfRange {
+        route: usize,
+        position: usize,
+        node: i32,
+    },
+    /// the depot appears inside a route.
+    DepotInRoute { route: usize, position: usize },
+    /// there are more non-empty routes than vehicles.
+    FleetExceeded {
+        vehicles: usize,
+        max_vehicles: usize,
+    },
+    /// the distance of a route exceeds the maximum distance of a route.
+    MaxDistanceExceeded {
+        route: usize,
+        distance: i32,
+        max_distance: i32,
+    },
+    /// the duration of a route exceeds the maximum duration of a route.
+    MaxDurationExceeded {
+        route: usize,
+        duration: i32,
+        max_duration: i32,
+    },
+    /// a visit arrives after the end of the customer's time window.
+    LateArrival {
+        route: usize,
+        position: usize,
+        arrival: i32,
+        latest: i32,
+    },
+    /// a route returns to the depot after the end of the depot's time window.
+    LateReturn {
+        route: usize,
+        return_time: i32,
+        latest: i32,
+    },
+    /// a customer is visited more often than its maximum number of visits.
+    MaxVisitsExceeded {
+        customer: i32,
+        visits: usize,
+        max_visits: usize,
+    },
+    /// a visit delivers less than the minimum load of the customer.
+    DeliveryTooSmall {
+        route: usize,
+        position: usize,
+        load: i32,
+        min_delivery: i32,
+    },
+    /// a route of a multi-depot instance has no depot or a depot that does not exist.
+    DepotOutOfRange { route: usize, depot: Option<usize> },
+    /// a route is served by a vehicle type that is not part of the fleet.
+    VehicleTypeOutOfRange { route: usize, vehicle_type: usize },
+    /// more routes are served by a vehicle type than there are vehicles of the type.
+    VehicleTypeExceeded {
+        vehicle_type: usize,
+        used: usize,
+        count: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::CapacityExceeded {
+                route,
+                load,
+                capacity,
+            } => write!(
+                f,
+                "route {} carries {} but capacity is {}",
+                route, load, capacity
+            ),
+            Violation::UnderDelivered {
+                customer,
+                delivered,
+                demand,
+            } => write!(
+                f,
+                "customer {} receives {} of its demand {}",
+                customer, delivered, demand
+            ),
+            Violation::OverDelivered {
+                customer,
+                delivered,
+                demand,
+            } => write!(
+                f,
+                "customer {} receives {} but its demand is {}",
+                customer, delivered, demand
+            ),
+            Violation::NonPositiveLoad {
+                route,
+                position,
+                load,
+            } => write!(
+                f,
+                "visit {} of route {} delivers non-positive load {}",
+                position, route, load
+            ),
+            Violation::NodeOutOfRange {
+                route,
+                position,
+                node,
+            } => write!(
+                f,
+                "visit {} of route {} refers to unknown node {}",
+                position, route, node
+            ),
+            Violation::DepotInRoute { route, position } => {
+                write!(f, "visit {} of route {} is the depot", position, route)
+            }
+            Violation::FleetExceeded {
+                vehicles,
+                max_vehicles,
+            } => write!(
+                f,
+                "{} routes are used but only {} vehicles are available",
+                vehicles, max_vehicles
+            ),
+            Violation::MaxDistanceExceeded {
+                route,
+                distance,
+                max_distance,
+            } => write!(
+                f,
+                "route {} travels {} but the maximum is {}",
+                route, distance, max_distance
+            ),
+            Violation::MaxDurationExceeded {
+                route,
+                duration,
+                max_duration,
+            } => write!(
+                f,
+                "route {} takes {} but the maximum is {}",
+                route, duration, max_duration
+            ),
+            Violation::DepotOutOfRange {
+                route,
+                depot: Some(depot),
+            } => write!(f, "route {} starts at unknown depot {}", route, depot),
+            Violation::DepotOutOfRange { route, depot: None } => {
+                write!(f, "route {} has no depot", route)
+            }
+            Violation::MaxVisitsExceeded {
+                customer,
+                visits,
+                max_visits,
+            } => write!(
+                f,
+                "customer {} is visited {} times but at most {} are allowed",
+                customer, visits, max_visits
+            ),
+            Violation::DeliveryTooSmall {
+                route,
+                position,
+                load,
+                min_delivery,
+            } => write!(
+                f,
+                "visit {} of route {} delivers {} but the minimum is {}",
+                position, route, load, min_delivery
+            ),
+            Violation::LateArrival {
+                route,
+                position,
+                arrival,
+                latest,
+            } => write!(
+                f,
+                "visit {} of route {} arrives at {} but the time window ends at {}",
+                position, route, arrival, latest
+            ),
+            Violation::LateReturn {
+                route,
+                return_time,
+                latest,
+            } => write!(
+                f,
+                "route {} returns at {} but the depot closes at {}",
+                route, return_time, latest
+            ),
+            Violation::VehicleTypeOutOfRange {
+                route,
+                vehicle_type,
+            } => write!(
+                f,
+                "route {} is served by unknown vehicle type {}",
+                route, vehicle_type
+            ),
+            Violation::VehicleTypeExceeded {
+                vehicle_type,
+                used,
+                count,
+            } => write!(
+                f,
+                "vehicle type {} serves {} routes but has {} vehicles",
+                vehicle_type, used, count
+            ),
+        }
+    }
+}
+
+/// checks `routes` of `(customer, load)` pairs against `instance`, returning every violation found.
+///
+/// an empty result means the routes form a feasible solution.
+pub fn check_feasibility<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    routes: &[Vec<(i32, i32)>],
+) -> Vec<Violation> {
+    let demands = instance.demands();
+    let mut delivered = vec![0i64; demands.len()];
+    let mut visited = vec![0; demands.len()];
+    let mut violations = vec![];
+    for (route, visits) in routes.iter().enumerate() {
+        let load = route_peak_load(instance, visits);
+        if load > instance.capacity() {
+            violations.push(Violation::CapacityExceeded {
+                route,
+                load,
+                capacity: instance.capacity(),
+            });
+        }
+        for (position, &(node, load)) in visits.iter().enumerate() {
+            if node == 0 {
+                violations.push(Violation::DepotInRoute { route, position });
+            } else if node < 0 || node as usize > demands.len() {
+                violations.push(Violation::NodeOutOfRange {
+                    route,
+                    position,
+                    node,
+                });
+            } else {
+                delivered[node as usize - 1] += load as i64;
+                visited[node as usize - 1] += 1;
+            }
+            if load <= 0 {
+                violations.push(Violation::NonPositiveLoad {
+                    route,
+                    position,
+                    load,
+                });
+            } else if node > 0 && node as usize <= demands.len() {
+                let min_delivery = min_delivery(instance, node as usize);
+                if load < min_delivery {
+                    violations.push(Violation::DeliveryTooSmall {
+                        route,
+                        position,
+                        load,
+                        min_delivery,
+                    });
+                }
+            }
+        }
+        if visits
+            .iter()
+            .all(|&(node, _)| node > 0 && node as usize <= demands.len())
+        {
+            let customers = visits
+                .iter()
+                .map(|&(node, _)| node as usize)
+                .collect::<Vec<_>>();
+            let distance = route_distance(instance, &customers);
+            if let Some(max_distance) = instance.max_route_distance()
+                && distance > max_distance
+            {
+                violations.push(Violation::MaxDistanceExceeded {
+                    route,
+                    distance,
+                    max_distance,
+                });
+            }
+            let duration = instance.route_duration(&customers);
+            if let Some(max_duration) = instance.max_route_duration()
+                && duration > max_duration
+            {
+                violations.push(Violation::MaxDurationExceeded {
+                    route,
+                    duration,
+                    max_duration,
+                });
+            }
+            if let Some(time_windows) = instance.time_windows() {
+                let schedule = route_schedule(instance, &customers);
+                for (position, (&customer, &arrival)) in
+                    customers.iter().zip(&schedule.arrivals).enumerate()
+                {
+                    if let Some(&(_, latest)) = time_windows.get(customer)
+                        && arrival > latest
+                    {
+                        violations.push(Violation::LateArrival {
+                            route,
+                            position,
+                            arrival,
+                            latest,
+                        });
+                    }
+                }
+                if let Some(&(_, latest)) = time_windows.first()
+                    && schedule.return_time > latest
+                {
+                    violations.push(Violation::LateReturn {
+                        route,
+                        return_time: schedule.return_time,
+                        latest,
+                    });
+                }
+            }
+        }
+    }
+    let vehicles = routes.iter().filter(|visits| !visits.is_empty()).count();
+    if let Some(max_vehicles) = instance.max_vehicles()
+        && vehicles > max_vehicles
+    {
+        violations.push(Violation::FleetExceeded {
+            vehicles,
+            max_vehicles,
+        });
+    }
+    for (i, &visits) in visited.iter().enumerate() {
+        if let Some(max_visits) = visit_limit(instance, i + 1)
+            && visits > max_visits
+        {
+            violations.push(Violation::MaxVisitsExceeded {
+                customer: i as i32 + 1,
+                visits,
+                max_visits,
+            });
+        }
+    }
+    for (i, (&delivered, &demand)) in delivered.iter().zip(demands).enumerate() {
+        let customer = i as i32 + 1;
+        if delivered < demand as i64 && instance.unserved_penalties().is_none() {
+            violations.push(Violation::UnderDelivered {
+                customer,
+                delivered: saturate(delivered),
+                demand,
+            });
+        } else if delivered > demand as i64 {
+            violations.push(Violation::OverDelivered {
+                customer,
+                delivered: saturate(delivered),
+                demand,
+            });
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::Instance;
+
+    fn instance() -> Instance {
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+    }
+
+    #[test]
+    fn test_check_feasibility() {
+        let instance = instance();
+        assert!(
+            check_feasibility(&instance, &[vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]]).is_empty()
+        );
+        assert_eq!(
+            check_feasibility(
+                &instance,
+                &[vec![(1, 6), (2, 5)], vec![(0, 1), (3, 0), (4, 2)]]
+            ),
+            vec![
+                Violation::CapacityExceeded {
+                    route: 0,
+                    load: 11,
+                    capacity: 10
+                },
+                Violation::DepotInRoute {
+                    route: 1,
+                    position: 0
+                },
+                Violation::NonPositiveLoad {
+                    route: 1,
+                    position: 1,
+                    load: 0
+                },
+                Violation::NodeOutOfRange {
+                    route: 1,
+                    position: 2,
+                    node: 4
+                },
+                Violation::UnderDelivered {
+                    customer: 2,
+                    delivered: 5,
+                    demand: 8
+                },
+                Violation::UnderDelivered {
+                    customer: 3,
+                    delivered: 0,
+                    demand: 4
+                },
+            ]
+        );
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(1, 7), (2, 3)], vec![(2, 5), (3, 4)]]),
+            vec![Violation::OverDelivered {
+                customer: 1,
+                delivered: 7,
+                demand: 6
+            }]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let far = i32::MAX / 2;
+        let instance = Instance::from_dense_matrix(
+            i32::MAX,
+            vec![i32::MAX, i32::MAX],
+            vec![vec![0, far, far], vec![far, 0, far], vec![far, far, 0]],
+        );
+        let routes = [vec![(1, i32::MAX), (2, i32::MAX), (1, 1)]];
+        assert_eq!(route_distance(&instance, &[1, 2, 1]), i32::MAX);
+        assert_eq!(route_peak_load(&instance, &routes[0]), i32::MAX);
+        assert_eq!(
+            check_feasibility(&instance, &routes),
+            vec![Violation::OverDelivered {
+                customer: 1,
+                delivered: i32::MAX,
+                demand: i32::MAX
+            }]
+        );
+    }
+
+    #[test]
+    fn test_max_vehicles() {
+        let instance = Instance::from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0), (0, 1)])
+            .with_max_vehicles(1)
+            .unwrap();
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(1, 5)], vec![(2, 5)]]),
+            vec![Violation::FleetExceeded {
+                vehicles: 2,
+                max_vehicles: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_route_limits() {
+        let instance = instance()
+            .with_service_times(vec![2, 3, 4])
+            .unwrap()
+            .with_max_route_distance(21)
+            .unwrap()
+            .with_max_route_duration(28)
+            .unwrap();
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]]),
+            vec![
+                Violation::MaxDistanceExceeded {
+                    route: 1,
+                    distance: 22,
+                    max_distance: 21
+                },
+                Violation::MaxDurationExceeded {
+                    route: 1,
+                    duration: 29,
+                    max_duration: 28
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_windows() {
+        let instance = instance()
+            .with_service_times(vec![2, 3, 4])
+            .unwrap()
+            .with_time_windows(vec![(0, 29), (10, 20), (0, 15), (0, 100)])
+            .unwrap();
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]]),
+            vec![
+                Violation::LateArrival {
+                    route: 0,
+                    position: 1,
+                    arrival: 17,
+                    latest: 15
+                },
+                Violation::LateReturn {
+                    route: 0,
+                    return_time: 30,
+                    latest: 29
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_policy() {
+        let instance = instance()
+            .with_max_visits(vec![2, 1, 2])
+            .unwrap()
+            .with_min_delivery(5)
+            .unwrap();
+        // customer 3 receives its whole demand of 4, which is allowed below the minimum.
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]]),
+            vec![
+                Violation::DeliveryTooSmall {
+                    route: 0,
+                    position: 1,
+                    load: 4,
+                    min_delivery: 5
+                },
+                Violation::DeliveryTooSmall {
+                    route: 1,
+                    position: 0,
+                    load: 4,
+                    min_delivery: 5
+                },
+                Violation::MaxVisitsExceeded {
+                    customer: 2,
+                    visits: 2,
+                    max_visits: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pickups() {
+        let instance = Instance::from_coord_list(10, vec![6, 4], vec![(0, 0), (10, 0), (20, 0)])
+            .with_pickups(vec![8, 0])
+            .unwrap();
+        // collecting 8 at customer 1 before delivering 4 to customer 2 overloads the vehicle.
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(1, 6), (2, 4)]]),
+            vec![Violation::CapacityExceeded {
+                route: 0,
+                load: 12,
+                capacity: 10
+            }]
+        );
+        assert!(check_feasibility(&instance, &[vec![(2, 4), (1, 6)]]).is_empty());
+        assert_eq!(
+            check_feasibility(&instance, &[vec![(2, 4), (1, 3)], vec![(1, 3)]]),
+            vec![Violation::MaxVisitsExceeded {
+                customer: 1,
+                visits: 2,
+                max_visits: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::instance::{AlkaidInstance, route_peak_load};
+use crate::moves::Routes;
+use crate::solution::{Route, Solution};
+
+/// a kind of vehicle of a heterogeneous fleet.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VehicleType {
+    /// the capacity of one vehicle.
+    pub capacity: i32,
+    /// the number of vehicles of this type.
+    pub count: usize,
+    /// the cost of using one vehicle, paid once per route.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fixed_cost: i32,
+    /// the factor applied to the distance of a route served by this type.
+    #[cfg_attr(feature = "serde", serde(default = "default_distance_multiplier"))]
+    pub distance_multiplier: f64,
+}
+
+#[cfg(feature = "serde")]
+fn default_distance_multiplier() -> f64 {
+    1.0
+}
+
+impl VehicleType {
+    /// creates a vehicle type without fixed cost and with a distance multiplier of `1`.
+    pub fn new(capacity: i32, count: usize) -> Self {
+        Self {
+            capacity,
+            count,
+            fixed_cost: 0,
+            distance_multiplier: 1.0,
+        }
+    }
+
+    /// the cost of serving a route of `distance` with a vehicle of this type.
+    pub fn route_cost(&self, distance: i32) -> i64 {
+        self.fixed_cost as i64 + (self.distance_multiplier * distance as f64).round() as i64
+    }
+}
+
+/// assigns a vehicle type to every route, minimizing the sum of [`VehicleType::route_cost`].
+///
+/// returns the index into `fleet` of the type of every route, or `None` if the routes cannot be
+/// served by the vehicles of the fleet.
+pub fn assign_vehicle_types(fleet: &[VehicleType], routes: &[Route]) -> Option<Vec<usize>> {
+    // every route needs its own vehicle, so no type needs more slots than there are routes.
+    let slots = fleet
+        .iter()
+        .enumerate()
+        .flat_map(|(i, vehicle_type)| std::iter::repeat_n(i, vehicle_type.count.min(routes.len())))
+        .collect::<Vec<_>>();
+    if slots.len() < routes.len() {
+        return None;
+    }
+    let costs = routes
+        .iter()
+        .map(|route| {
+            slots
+                .iter()
+                .map(|&i| {
+                    if route.peak_load <= fleet[i].capacity {
+                        Some(fleet[i].route_cost(route.distance))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let assignment = min_cost_assignment(&costs, slots.len());
+    assignment
+        .iter()
+        .enumerate()
+        .map(|(route, &slot)| costs[route][slot].map(|_| slots[slot]))
+        .collect()
+}
+
+/// fits the routes of `solution`, found for vehicles of the largest capacity of the
+/// [`fleet`](AlkaidInstance::fleet), to the vehicles of the fleet.
+///
+/// the routes are matched to vehicles such that as little load as possible exceeds the capacity of
+/// the vehicles, and routes beyond the number of vehicles are emptied. the load that exceeds a
+/// capacity is taken from the last visits of the route, in parts where the split policy allows it,
+/// and delivered by the cheapest insertions into the routes with room left, including the routes of
+/// unused vehicles. then the cheapest vehicle types are assigned with [`assign_vehicle_types`].
+///
+/// returns `None` if some load cannot be delivered by the vehicles of the fleet, unless the
+/// instance has [`unserved_penalties`](AlkaidInstance::unserved_penalties), which leave it
+/// unserved.
+pub fn fit_fleet<T: AlkaidInstance + ?Sized>(instance: &T, solution: Solution) -> Option<Solution> {
+    let Some(fleet) = instance.fleet() else {
+        return Some(solution);
+    };
+    let mut routes = solution.routes;
+    // no route holds more visits than the whole solution, so no more vehicles can be used.
+    let num_visits = routes.iter().map(|route| route.visits.len()).sum::<usize>();
+    let slots = fleet
+        .iter()
+        .enumerate()
+        .flat_map(|(i, vehicle_type)| std::iter::repeat_n(i, vehicle_type.count.min(num_visits)))
+        .collect::<Vec<_>>();
+    // the routes beyond the number of vehicles deliver the least.
+    routes.sort_by_key(|route| std::cmp::Reverse(route.load));
+    let mut excess = routes
+        .split_off(slots.len().min(routes.len()))
+        .into_iter()
+        .flat_map(|route| route.visits)
+        .collect::<Vec<_>>();
+    // any overload costs more than all routes together.
+    let overload_cost = routes
+        .iter()
+        .map(|route| {
+            fleet
+                .iter()
+                .map(|vehicle_type| vehicle_type.route_cost(route.distance))
+                .max()
+                .unwrap_or(0)
+        })
+        .fold(1, i64::saturating_add);
+    let costs = routes
+        .iter()
+        .map(|route| {
+            slots
+                .iter()
+                .map(|&i| {
+                    let overload = (route.peak_load - fleet[i].capacity).max(0) as i64;
+                    Some(
+                        overload
+                            .saturating_mul(overload_cost)
+                            .saturating_add(fleet[i].route_cost(route.distance)),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut visits = vec![vec![]; slots.len()];
+    for (route, slot) in routes
+        .into_iter()
+        .zip(min_cost_assignment(&costs, slots.len()))
+    {
+        visits[slot] = route.visits;
+    }
+    let capacities = slots.iter().map(|&i| fleet[i].capacity).collect();
+    let mut routes = Routes::new(instance, visits, capacities);
+    for route in 0..slots.len() {
+        loop {
+            let overload =
+                route_peak_load(instance, &routes.visits[route]) - routes.capacities[route];
+            if overload <= 0 {
+                break;
+            }
+            let last = routes.visits[route].len() - 1;
+            excess.push(routes.take(route, last, overload));
+        }
+    }
+    excess.sort_by_key(|&(_, load)| std::cmp::Reverse(load));
+    for (customer, load) in excess {
+        if routes.deliver(customer as usize, load) > 0 && instance.unserved_penalties().is_none() {
+            return None;
+        }
+    }
+    let mut solution = Solution::new(instance, routes.visits, 0);
+    let vehicle_types = assign_vehicle_types(fleet, &solution.routes)?;
+    for (route, vehicle_type) in solution.routes.iter_mut().zip(vehicle_types) {
+        route.vehicle_type = Some(vehicle_type);
+    }
+    solution.objective = solution.penalized_cost(instance);
+    Some(solution)
+}
+
+/// the hungarian method: assigns every row to a distinct column, minimizing the total cost, where
+/// `None` marks a forbidden pair. requires at least as many columns as rows.
+fn min_cost_assignment(costs: &[Vec<Option<i64>>], num_columns: usize) -> Vec<usize> {
+    // large enough to never be chosen over an allowed pair, small enough to never overflow.
+    let forbidden = costs
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|cost| cost.abs())
+        .sum::<i64>()
+        + 1;
+    let cost = |row: usize, column: usize| costs[row - 1][column - 1].unwrap_or(forbidden);
+    let num_rows = costs.len();
+    // rows and columns count from `1`, `0` is a virtual column.
+    let mut u = vec![0; num_rows + 1];
+    let mut v = vec![0; num_columns + 1];
+    let mut row_of = vec![0; num_columns + 1];
+    let mut way = vec![0; num_columns + 1];
+    for row in 1..=num_rows {
+        row_of[0] = row;
+        let mut column = 0;
+        let mut min = vec![i64::MAX; num_columns + 1];
+        let mut used = vec![false; num_columns + 1];
+        loop {
+            used[column] = true;
+            let current_row = row_of[column];
+            let mut delta = i64::MAX;
+            let mut next_column = 0;
+            for j in 1..=num_columns {
+                if !used[j] {
+                    let reduced = cost(current_row, j) - u[current_row] - v[j];
+                    if reduced < min[j] {
+                        min[j] = reduced;
+                        way[j] = column;
+                    }
+                    if min[j] < delta {
+                        delta = min[j];
+                        next_column = j;
+                    }
+                }
+            }
+            for j in 0..=num_columns {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+            column = next_column;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = way[column];
+            row_of[column] = row_of[previous];
+            column = previous;
+        }
+    }
+    let mut assignment = vec![0; num_rows];
+    for column in 1..=num_columns {
+        if row_of[column] != 0 {
+            assignment[row_of[column] - 1] = column - 1;
+        }
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::Instance;
+
+    fn fleet() -> Vec<VehicleType> {
+        let van = VehicleType {
+            fixed_cost: 10,
+            ..VehicleType::new(5, 2)
+        };
+        let truck = VehicleType {
+            fixed_cost: 30,
+            distance_multiplier: 1.5,
+            ..VehicleType::new(12, 1)
+        };
+        vec![van, truck]
+    }
+
+    fn instance() -> Instance {
+        Instance::from_coord_list(10, vec![10, 4, 3], vec![(0, 0), (3, 4), (0, 5), (-4, 3)])
+            .with_fleet(fleet())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_assign_vehicle_types() {
+        let instance = instance();
+        let solution = Solution::new(
+            &instance,
+            vec![vec![(1, 10)], vec![(2, 4)], vec![(3, 3)]],
+            0,
+        );
+        assert_eq!(
+            assign_vehicle_types(&fleet(), &solution.routes),
+            Some(vec![1, 0, 0])
+        );
+        assert_eq!(
+            assign_vehicle_types(
+                &[VehicleType::new(5, 1), fleet()[1].clone()],
+                &solution.routes
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fit_fleet() {
+        let instance = instance();
+        // the optimum for the truck alone: the second route is too much for a van, so part of it
+        // moves to the truck.
+        let solution = Solution::new(&instance, vec![vec![(1, 10)], vec![(2, 4), (3, 3)]], 0);
+        let solution = fit_fleet(&instance, solution).unwrap();
+        assert!(solution.violations(&instance).is_empty());
+        assert!(solution.unserved(&instance).is_empty());
+        assert_eq!(solution.num_vehicles(), 2);
+        assert_eq!(solution.objective as i64, solution.fleet_cost(&instance));
+    }
+
+    #[test]
+    fn test_route_cost() {
+        let truck = &fleet()[1];
+        assert_eq!(truck.route_cost(10), 30 + 15);
+        let expensive = VehicleType {
+            fixed_cost: i32::MAX,
+            distance_multiplier: 2.0,
+            ..VehicleType::new(10, 1)
+        };
+        assert_eq!(
+            expensive.route_cost(i32::MAX),
+            i32::MAX as i64 + 2 * i32::MAX as i64
+        );
+    }
+}
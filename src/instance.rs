@@ -1,82 +1,899 @@
-#![allow(dead_code)]
+use crate::error::InstanceError;
+use crate::fleet::VehicleType;
 
-#[derive(Clone)]
-pub enum InputFormat {
-    DenseMatrix(Vec<Vec<i32>>),
-    CoordList(Vec<(i32, i32)>),
+/// how a distance computed from coordinates is turned into the integer distance used by the solver.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DistanceRounding {
+    /// rounds to the nearest integer, with halfway cases away from zero.
+    #[default]
+    Round,
+    /// rounds down.
+    Floor,
+    /// rounds up.
+    Ceil,
+    /// multiplies by the factor, then rounds to the nearest integer, e.g. `Scaled(10.0)` keeps
+    /// one decimal of the distance.
+    Scaled(f64),
 }
 
-impl InputFormat {
-    pub fn to_str(&self) -> &'static str {
+impl DistanceRounding {
+    /// turns `distance` into an integer distance.
+    pub fn apply(self, distance: f64) -> i32 {
         match self {
-            InputFormat::DenseMatrix(_) => "DENSE_MATRIX",
-            InputFormat::CoordList(_) => "COORD_LIST",
+            DistanceRounding::Round => distance.round() as i32,
+            DistanceRounding::Floor => distance.floor() as i32,
+            DistanceRounding::Ceil => distance.ceil() as i32,
+            DistanceRounding::Scaled(factor) => (distance * factor).round() as i32,
         }
     }
 
-    pub fn to_dense_matrix(&self) -> Vec<i32> {
+    /// whether every distance up to `max_distance` is an `i32` after rounding, as `apply`
+    /// saturates otherwise.
+    fn fits(self, max_distance: f64) -> bool {
+        let rounded = match self {
+            DistanceRounding::Round => max_distance.round(),
+            DistanceRounding::Floor => max_distance.floor(),
+            DistanceRounding::Ceil => max_distance.ceil(),
+            DistanceRounding::Scaled(factor) => (max_distance * factor).round(),
+        };
+        rounded <= i32::MAX as f64
+    }
+}
+
+/// how the distance of two points given as latitude and longitude is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GeoMetric {
+    /// the great-circle distance on a sphere with the mean earth radius, coordinates in decimal
+    /// degrees.
+    #[default]
+    Haversine,
+    /// the distance of the TSPLIB `GEO` edge weight type, coordinates in `DDD.MM` degrees and
+    /// minutes. TSPLIB adds one kilometre and truncates, which `Ceil` matches unless the distance
+    /// is a whole number of kilometres.
+    TsplibGeo,
+}
+
+/// the unit of a distance computed from latitude and longitude, before rounding.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DistanceUnit {
+    #[default]
+    Metres,
+    Kilometres,
+    Miles,
+}
+
+impl DistanceUnit {
+    /// converts a distance in kilometres to this unit.
+    fn convert(self, kilometres: f64) -> f64 {
+        match self {
+            DistanceUnit::Metres => kilometres * 1000.0,
+            DistanceUnit::Kilometres => kilometres,
+            DistanceUnit::Miles => kilometres / 1.609344,
+        }
+    }
+}
+
+/// the haversine distance in kilometres of two `(latitude, longitude)` points in decimal degrees.
+fn haversine(a: (f64, f64), b: (f64, f64)) -> f64 {
+    const EARTH_RADIUS: f64 = 6371.0088;
+    let (lat_a, lat_b) = (a.0.to_radians(), b.0.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.1 - a.1).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+/// converts a TSPLIB `DDD.MM` coordinate (degrees and minutes) to radians.
+#[allow(clippy::approx_constant)]
+fn geo_radians(x: f64) -> f64 {
+    // TSPLIB defines the distance with this truncated value of pi.
+    const PI: f64 = 3.141592;
+    let deg = x.trunc();
+    let min = x - deg;
+    PI * (deg + 5.0 * min / 3.0) / 180.0
+}
+
+/// the TSPLIB `GEO` distance in kilometres before TSPLIB's own rounding, where `x` is the latitude
+/// and `y` the longitude.
+pub(crate) fn tsplib_geo(a: (f64, f64), b: (f64, f64)) -> f64 {
+    const RRR: f64 = 6378.388;
+    let (lat_a, lon_a) = (geo_radians(a.0), geo_radians(a.1));
+    let (lat_b, lon_b) = (geo_radians(b.0), geo_radians(b.1));
+    let q1 = (lon_a - lon_b).cos();
+    let q2 = (lat_a - lat_b).cos();
+    let q3 = (lat_a + lat_b).cos();
+    RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos()
+}
+
+pub use input_format::InputFormat;
+
+// serde derives its impls next to the enum rather than in it, so the deprecated `CoordList` is
+// only allowed in a module of its own.
+#[allow(deprecated)]
+mod input_format {
+    use super::{DistanceRounding, DistanceUnit, GeoMetric};
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum InputFormat {
+        DenseMatrix(Vec<Vec<i32>>),
+        /// integer planar coordinates, the distance of two nodes is their euclidean distance
+        /// rounded to the nearest integer.
+        #[deprecated(
+            note = "use `InputFormat::Planar`, which takes `f64` coordinates and a rounding. \
+                    `Instance::from_coord_list` now builds `Planar` instead of `CoordList`"
+        )]
+        CoordList(Vec<(i32, i32)>),
+        /// planar coordinates, the distance of two nodes is their euclidean distance after
+        /// `rounding`.
+        Planar {
+            coords: Vec<(f64, f64)>,
+            #[cfg_attr(feature = "serde", serde(default))]
+            rounding: DistanceRounding,
+        },
+        /// `(latitude, longitude)` coordinates, the distance of two nodes is computed with `metric`
+        /// in `unit`, then turned into an integer with `rounding`.
+        LatLon {
+            coords: Vec<(f64, f64)>,
+            #[cfg_attr(feature = "serde", serde(default))]
+            metric: GeoMetric,
+            #[cfg_attr(feature = "serde", serde(default))]
+            unit: DistanceUnit,
+            #[cfg_attr(feature = "serde", serde(default))]
+            rounding: DistanceRounding,
+        },
+    }
+}
+
+impl InputFormat {
+    pub fn to_str(&self) -> &'static str {
         match self {
-            InputFormat::DenseMatrix(matrix) => matrix.iter().flat_map(|row| row.clone()).collect(),
-            InputFormat::CoordList(_) => vec![],
+            InputFormat::DenseMatrix(_) => "DENSE_MATRIX",
+            #[allow(deprecated)]
+            InputFormat::CoordList(_) => "COORD_LIST",
+            InputFormat::Planar { .. } => "COORD_LIST",
+            InputFormat::LatLon { .. } => "LAT_LON",
         }
     }
 
+    /// the x coordinates of a `CoordList`, or an empty list for any other format, including the
+    /// `Planar` input built by [`Instance::from_coord_list`].
+    #[deprecated(note = "use the `coords` of `InputFormat::Planar`, which \
+                         `Instance::from_coord_list` now builds")]
     pub fn to_coord_list_x(&self) -> Vec<i32> {
         match self {
-            InputFormat::DenseMatrix(_) => vec![],
+            #[allow(deprecated)]
             InputFormat::CoordList(coords) => coords.iter().map(|(x, _)| *x).collect(),
+            _ => vec![],
         }
     }
 
+    /// the y coordinates of a `CoordList`, or an empty list for any other format, including the
+    /// `Planar` input built by [`Instance::from_coord_list`].
+    #[deprecated(note = "use the `coords` of `InputFormat::Planar`, which \
+                         `Instance::from_coord_list` now builds")]
     pub fn to_coord_list_y(&self) -> Vec<i32> {
         match self {
-            InputFormat::DenseMatrix(_) => vec![],
+            #[allow(deprecated)]
             InputFormat::CoordList(coords) => coords.iter().map(|(_, y)| *y).collect(),
+            _ => vec![],
         }
     }
+
+    /// the number of nodes, including the depot.
+    pub fn num_nodes(&self) -> usize {
+        match self {
+            InputFormat::DenseMatrix(matrix) => matrix.len(),
+            #[allow(deprecated)]
+            InputFormat::CoordList(coords) => coords.len(),
+            InputFormat::Planar { coords, .. } | InputFormat::LatLon { coords, .. } => coords.len(),
+        }
+    }
+
+    /// the input restricted to `nodes`, where node `i` of the result is node `nodes[i]`.
+    pub(crate) fn select(&self, nodes: &[usize]) -> InputFormat {
+        let coords = |coords: &[(f64, f64)]| nodes.iter().map(|&node| coords[node]).collect();
+        match self {
+            InputFormat::DenseMatrix(matrix) => InputFormat::DenseMatrix(
+                nodes
+                    .iter()
+                    .map(|&i| nodes.iter().map(|&j| matrix[i][j]).collect())
+                    .collect(),
+            ),
+            #[allow(deprecated)]
+            InputFormat::CoordList(all) => {
+                InputFormat::CoordList(nodes.iter().map(|&node| all[node]).collect())
+            }
+            InputFormat::Planar {
+                coords: all,
+                rounding,
+            } => InputFormat::Planar {
+                coords: coords(all),
+                rounding: *rounding,
+            },
+            InputFormat::LatLon {
+                coords: all,
+                metric,
+                unit,
+                rounding,
+            } => InputFormat::LatLon {
+                coords: coords(all),
+                metric: *metric,
+                unit: *unit,
+                rounding: *rounding,
+            },
+        }
+    }
+
+    /// the full distance matrix in row-major order, computed with [`InputFormat::distance`].
+    pub fn to_dense_matrix(&self) -> Vec<i32> {
+        dense_matrix(self.num_nodes(), |i, j| self.distance(i, j))
+    }
+
+    /// the distance from node `from` to node `to`. the solver receives exactly these distances.
+    pub fn distance(&self, from: usize, to: usize) -> i32 {
+        match self {
+            InputFormat::DenseMatrix(matrix) => matrix[from][to],
+            #[allow(deprecated)]
+            InputFormat::CoordList(coords) => {
+                let dx = coords[from].0 as f64 - coords[to].0 as f64;
+                let dy = coords[from].1 as f64 - coords[to].1 as f64;
+                DistanceRounding::Round.apply(dx.hypot(dy))
+            }
+            InputFormat::Planar { coords, rounding } => {
+                let dx = coords[from].0 - coords[to].0;
+                let dy = coords[from].1 - coords[to].1;
+                rounding.apply(dx.hypot(dy))
+            }
+            InputFormat::LatLon {
+                coords,
+                metric,
+                unit,
+                rounding,
+            } => {
+                if from == to {
+                    return 0;
+                }
+                let distance = match metric {
+                    GeoMetric::Haversine => haversine(coords[from], coords[to]),
+                    GeoMetric::TsplibGeo => tsplib_geo(coords[from], coords[to]),
+                };
+                rounding.apply(unit.convert(distance))
+            }
+        }
+    }
+}
+
+/// the `num_nodes` by `num_nodes` matrix of `distance` in row-major order.
+pub(crate) fn dense_matrix(num_nodes: usize, distance: impl Fn(usize, usize) -> i32) -> Vec<i32> {
+    (0..num_nodes)
+        .flat_map(|i| (0..num_nodes).map(move |j| (i, j)))
+        .map(|(i, j)| distance(i, j))
+        .collect()
 }
 
 pub trait AlkaidInstance {
     fn capacity(&self) -> i32;
     fn demands(&self) -> &[i32];
     fn input_format(&self) -> &InputFormat;
+
+    /// the distance from node `from` to node `to`, where node `0` is the depot.
+    fn distance(&self, from: usize, to: usize) -> i32 {
+        self.input_format().distance(from, to)
+    }
+
+    /// the number of vehicles available, i.e. the maximum number of routes, or `None` if the
+    /// fleet is unlimited.
+    fn max_vehicles(&self) -> Option<usize> {
+        None
+    }
+
+    /// the vehicle types of a heterogeneous fleet, or `None` if every vehicle has
+    /// [`capacity`](AlkaidInstance::capacity). the capacity must then be the largest capacity of
+    /// the fleet.
+    fn fleet(&self) -> Option<&[VehicleType]> {
+        None
+    }
+
+    /// the maximum distance of a route, see [`route_distance`].
+    fn max_route_distance(&self) -> Option<i32> {
+        None
+    }
+
+    /// the maximum duration of a route, see [`AlkaidInstance::route_duration`].
+    fn max_route_duration(&self) -> Option<i32> {
+        None
+    }
+
+    /// the time spent at every visit of each customer, `service_times()[i - 1]` for customer `i`,
+    /// or `None` if visits take no time.
+    fn service_times(&self) -> Option<&[i32]> {
+        None
+    }
+
+    /// the time to travel from node `from` to node `to`, the distance unless the instance has a
+    /// travel time matrix.
+    fn travel_time(&self, from: usize, to: usize) -> i32 {
+        self.distance(from, to)
+    }
+
+    /// the duration of a route visiting `customers` in order, from leaving to returning to the
+    /// depot: its travel times plus the service times of its visits and the waiting for time
+    /// windows, see [`route_schedule`].
+    fn route_duration(&self, customers: &[usize]) -> i32 {
+        let schedule = route_schedule(self, customers);
+        schedule.return_time - schedule.departure
+    }
+
+    /// the `(earliest, latest)` arrival time at every node, including the depot at index `0` whose
+    /// window bounds the departure and return of every route, or `None` if arrivals are not
+    /// restricted.
+    fn time_windows(&self) -> Option<&[(i32, i32)]> {
+        None
+    }
+
+    /// the maximum number of visits to each customer, `max_visits()[i - 1]` for customer `i`, or
+    /// `None` if the demand of every customer may be split arbitrarily.
+    fn max_visits(&self) -> Option<&[usize]> {
+        None
+    }
+
+    /// the minimum load of a visit to each customer, `min_deliveries()[i - 1]` for customer `i`,
+    /// or `None` if any positive load is allowed, see [`min_delivery`].
+    fn min_deliveries(&self) -> Option<&[i32]> {
+        None
+    }
+
+    /// whether routes end at their last customer, so the leg back to the depot is neither
+    /// travelled nor paid. the distances the solver sees are then asymmetric, so the search leaves
+    /// out the operators that [`assume symmetry`](crate::config::InterOperators::assumes_symmetry).
+    fn open_routes(&self) -> bool {
+        false
+    }
+
+    /// the penalty per unit of demand left undelivered of each customer, `unserved_penalties()[i - 1]`
+    /// for customer `i`, or `None` if every demand must be delivered. equivalently, the prize
+    /// collected per unit delivered.
+    fn unserved_penalties(&self) -> Option<&[i32]> {
+        None
+    }
+
+    /// the quantity collected from each customer and brought back to the depot,
+    /// `pickups()[i - 1]` for customer `i`, or `None` if nothing is collected. a customer with a
+    /// pickup is visited once, delivering its whole demand and collecting its whole pickup, see
+    /// [`route_peak_load`].
+    fn pickups(&self) -> Option<&[i32]> {
+        None
+    }
+}
+
+/// the most visits `customer` may receive, from its [`max_visits`](AlkaidInstance::max_visits)
+/// and a single visit if it has a pickup.
+pub(crate) fn visit_limit<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    customer: usize,
+) -> Option<usize> {
+    let has_pickup = instance
+        .pickups()
+        .and_then(|pickups| pickups.get(customer - 1))
+        .is_some_and(|&pickup| pickup > 0);
+    let max_visits = instance
+        .max_visits()
+        .and_then(|max_visits| max_visits.get(customer - 1).copied());
+    if has_pickup { Some(1) } else { max_visits }
+}
+
+/// the smallest load a visit may deliver to `customer`. a visit delivering the whole demand is
+/// always allowed, even if the demand is below the minimum of the customer.
+pub fn min_delivery<T: AlkaidInstance + ?Sized>(instance: &T, customer: usize) -> i32 {
+    let demand = instance.demands()[customer - 1];
+    instance
+        .min_deliveries()
+        .and_then(|min_deliveries| min_deliveries.get(customer - 1))
+        .map_or(1, |&min_delivery| min_delivery.min(demand))
+}
+
+/// the largest load on board of a route delivering `visits` in order: the vehicle leaves the
+/// depot with all deliveries of the route, and every visit unloads its delivery and loads the
+/// pickup of its customer. without pickups, the total delivery of the route. saturated at the
+/// bounds of `i32`.
+pub fn route_peak_load<T: AlkaidInstance + ?Sized>(instance: &T, visits: &[(i32, i32)]) -> i32 {
+    let pickups = instance.pickups().unwrap_or_default();
+    let mut load = visits.iter().map(|&(_, load)| load as i64).sum::<i64>();
+    let mut peak = load;
+    for &(customer, delivery) in visits {
+        let pickup = (customer as usize)
+            .checked_sub(1)
+            .and_then(|i| pickups.get(i))
+            .copied()
+            .unwrap_or(0);
+        load += pickup as i64 - delivery as i64;
+        peak = peak.max(load);
+    }
+    saturate(peak)
+}
+
+/// the times of a route, computed by [`route_schedule`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// the time the route leaves the depot, the start of the depot's time window or `0`.
+    pub departure: i32,
+    /// the arrival time at every visit. service starts at the arrival or at the start of the
+    /// customer's time window, whichever is later.
+    pub arrivals: Vec<i32>,
+    /// the time the route returns to the depot, or leaves its last customer if routes are open.
+    pub return_time: i32,
+}
+
+/// the schedule of a route visiting `customers` in order, leaving the depot as early as possible
+/// and waiting at a customer until its time window opens.
+pub fn route_schedule<T: AlkaidInstance + ?Sized>(instance: &T, customers: &[usize]) -> Schedule {
+    let time_windows = instance.time_windows().unwrap_or_default();
+    let service_times = instance.service_times().unwrap_or_default();
+    let departure = time_windows.first().map_or(0, |&(earliest, _)| earliest);
+    let mut arrivals = Vec::with_capacity(customers.len());
+    let mut time = departure;
+    let mut node = 0;
+    for &customer in customers {
+        let arrival = time.saturating_add(instance.travel_time(node, customer));
+        arrivals.push(arrival);
+        let earliest = time_windows
+            .get(customer)
+            .map_or(arrival, |&(earliest, _)| earliest);
+        let service_time = customer
+            .checked_sub(1)
+            .and_then(|i| service_times.get(i))
+            .copied()
+            .unwrap_or(0);
+        time = arrival.max(earliest).saturating_add(service_time);
+        node = customer;
+    }
+    let return_time = if customers.is_empty() {
+        departure
+    } else if instance.open_routes() {
+        time
+    } else {
+        time.saturating_add(instance.travel_time(node, 0))
+    };
+    Schedule {
+        departure,
+        arrivals,
+        return_time,
+    }
+}
+
+/// whether a route visiting `customers` in order arrives at every customer and returns to the
+/// depot before the end of their time windows.
+pub fn is_on_time<T: AlkaidInstance + ?Sized>(instance: &T, customers: &[usize]) -> bool {
+    let Some(time_windows) = instance.time_windows() else {
+        return true;
+    };
+    let schedule = route_schedule(instance, customers);
+    customers
+        .iter()
+        .zip(&schedule.arrivals)
+        .all(|(&customer, &arrival)| {
+            time_windows
+                .get(customer)
+                .is_none_or(|&(_, latest)| arrival <= latest)
+        })
+        && time_windows
+            .first()
+            .is_none_or(|&(_, latest)| schedule.return_time <= latest)
 }
 
+/// the distance of a route visiting `customers` in order, including the legs from and back to
+/// the depot, or only from the depot if routes are open. saturated at `i32::MAX`.
+pub fn route_distance<T: AlkaidInstance + ?Sized>(instance: &T, customers: &[usize]) -> i32 {
+    if customers.is_empty() {
+        return 0;
+    }
+    let back = if instance.open_routes() {
+        0
+    } else {
+        instance.distance(customers[customers.len() - 1], 0)
+    };
+    let distance = instance.distance(0, customers[0]) as i64
+        + customers
+            .windows(2)
+            .map(|w| instance.distance(w[0], w[1]) as i64)
+            .sum::<i64>()
+        + back as i64;
+    saturate(distance)
+}
+
+/// `value` as an `i32`, saturated at the bounds of `i32`. sums of loads and distances are
+/// accumulated in `i64` and saturated, so that they cannot overflow.
+pub(crate) fn saturate(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawInstance")
+)]
 pub struct Instance {
     capacity: i32,
     demands: Vec<i32>,
     input: InputFormat,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    max_vehicles: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    fleet: Option<Vec<VehicleType>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    max_route_distance: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    max_route_duration: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    service_times: Option<Vec<i32>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    time_windows: Option<Vec<(i32, i32)>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    travel_times: Option<Vec<Vec<i32>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    max_visits: Option<Vec<usize>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    min_deliveries: Option<Vec<i32>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "std::ops::Not::not"))]
+    open_routes: bool,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    unserved_penalties: Option<Vec<i32>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pickups: Option<Vec<i32>>,
 }
 
 impl Instance {
+    /// creates an instance from a dense distance matrix, where node `0` is the depot.
+    ///
+    /// # Panics
+    /// panics if the data is rejected by [`Instance::try_from_dense_matrix`].
     pub fn from_dense_matrix(capacity: i32, demands: Vec<i32>, matrix: Vec<Vec<i32>>) -> Self {
-        assert!(
-            demands.len() + 1 == matrix.len() && matrix.iter().all(|row| row.len() == matrix.len())
-        );
-        assert!(
-            matrix
-                .iter()
-                .all(|row| row.len() == matrix.len() && matrix.len() == demands.len() + 1)
-        );
-        assert!(
-            (0..matrix.len())
-                .flat_map(|i| (0..i).map(move |j| (i, j)))
-                .all(|(i, j)| matrix[i][j] == matrix[j][i])
-        );
-        Self {
+        Self::try_from_dense_matrix(capacity, demands, matrix).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// creates an instance from a list of coordinates, where node `0` is the depot, rounding the
+    /// distances to the nearest integer. the coordinates are kept as [`InputFormat::Planar`], not
+    /// as the deprecated `InputFormat::CoordList`.
+    ///
+    /// # Panics
+    /// panics if the data is rejected by [`Instance::try_from_coord_list`].
+    pub fn from_coord_list<C: Into<f64>>(
+        capacity: i32,
+        demands: Vec<i32>,
+        coords: Vec<(C, C)>,
+    ) -> Self {
+        Self::try_from_coord_list(capacity, demands, coords).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// creates an instance from a list of coordinates, where node `0` is the depot, turning the
+    /// distances into integers with `rounding`.
+    ///
+    /// # Panics
+    /// panics if the data is rejected by [`Instance::try_from_coord_list_with_rounding`].
+    pub fn from_coord_list_with_rounding<C: Into<f64>>(
+        capacity: i32,
+        demands: Vec<i32>,
+        coords: Vec<(C, C)>,
+        rounding: DistanceRounding,
+    ) -> Self {
+        Self::try_from_coord_list_with_rounding(capacity, demands, coords, rounding)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// creates an instance from `(latitude, longitude)` coordinates, where node `0` is the depot.
+    ///
+    /// # Panics
+    /// panics if the data is rejected by [`Instance::try_from_lat_lon`].
+    pub fn from_lat_lon(
+        capacity: i32,
+        demands: Vec<i32>,
+        coords: Vec<(f64, f64)>,
+        metric: GeoMetric,
+        unit: DistanceUnit,
+        rounding: DistanceRounding,
+    ) -> Self {
+        Self::try_from_lat_lon(capacity, demands, coords, metric, unit, rounding)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// creates an instance from a dense distance matrix, where node `0` is the depot,
+    /// returning the first rule violated by the data.
+    pub fn try_from_dense_matrix(
+        capacity: i32,
+        demands: Vec<i32>,
+        matrix: Vec<Vec<i32>>,
+    ) -> Result<Self, InstanceError> {
+        Self::try_new(capacity, demands, InputFormat::DenseMatrix(matrix))
+    }
+
+    /// creates an instance from a list of coordinates, where node `0` is the depot, rounding the
+    /// distances to the nearest integer and returning the first rule violated by the data.
+    pub fn try_from_coord_list<C: Into<f64>>(
+        capacity: i32,
+        demands: Vec<i32>,
+        coords: Vec<(C, C)>,
+    ) -> Result<Self, InstanceError> {
+        Self::try_from_coord_list_with_rounding(
             capacity,
             demands,
-            input: InputFormat::DenseMatrix(matrix),
-        }
+            coords,
+            DistanceRounding::default(),
+        )
+    }
+
+    /// creates an instance from a list of coordinates, where node `0` is the depot, turning the
+    /// distances into integers with `rounding` and returning the first rule violated by the data.
+    pub fn try_from_coord_list_with_rounding<C: Into<f64>>(
+        capacity: i32,
+        demands: Vec<i32>,
+        coords: Vec<(C, C)>,
+        rounding: DistanceRounding,
+    ) -> Result<Self, InstanceError> {
+        let coords = coords
+            .into_iter()
+            .map(|(x, y)| (x.into(), y.into()))
+            .collect();
+        Self::try_new(capacity, demands, InputFormat::Planar { coords, rounding })
     }
 
-    pub fn from_coord_list(capacity: i32, demands: Vec<i32>, coords: Vec<(i32, i32)>) -> Self {
-        assert!(demands.len() + 1 == coords.len());
-        Self {
+    /// creates an instance from `(latitude, longitude)` coordinates, where node `0` is the depot,
+    /// returning the first rule violated by the data. the distances are computed with `metric` in
+    /// `unit` and turned into integers with `rounding`, e.g. `DistanceRounding::Scaled(10.0)` with
+    /// `DistanceUnit::Kilometres` gives distances in units of 100 metres.
+    pub fn try_from_lat_lon(
+        capacity: i32,
+        demands: Vec<i32>,
+        coords: Vec<(f64, f64)>,
+        metric: GeoMetric,
+        unit: DistanceUnit,
+        rounding: DistanceRounding,
+    ) -> Result<Self, InstanceError> {
+        let input = InputFormat::LatLon {
+            coords,
+            metric,
+            unit,
+            rounding,
+        };
+        Self::try_new(capacity, demands, input)
+    }
+
+    /// creates an instance without constraints beyond the capacity, returning the first rule
+    /// violated by the data.
+    pub(crate) fn try_new(
+        capacity: i32,
+        demands: Vec<i32>,
+        input: InputFormat,
+    ) -> Result<Self, InstanceError> {
+        let instance = Self {
             capacity,
             demands,
-            input: InputFormat::CoordList(coords),
+            input,
+            max_vehicles: None,
+            fleet: None,
+            max_route_distance: None,
+            max_route_duration: None,
+            service_times: None,
+            time_windows: None,
+            travel_times: None,
+            max_visits: None,
+            min_deliveries: None,
+            open_routes: false,
+            unserved_penalties: None,
+            pickups: None,
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// limits the fleet to `max_vehicles` vehicles, returning an error if they cannot carry the
+    /// total demand.
+    pub fn with_max_vehicles(self, max_vehicles: usize) -> Result<Self, InstanceError> {
+        let instance = Self {
+            max_vehicles: Some(max_vehicles),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// replaces the vehicles of the instance by a heterogeneous `fleet`. the capacity of the
+    /// instance becomes the largest capacity of the fleet.
+    pub fn with_fleet(self, fleet: Vec<VehicleType>) -> Result<Self, InstanceError> {
+        let instance = Self {
+            capacity: fleet
+                .iter()
+                .map(|vehicle_type| vehicle_type.capacity)
+                .max()
+                .unwrap_or(self.capacity),
+            fleet: Some(fleet),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// limits the distance travelled by every route, including the legs from and back to the
+    /// depot.
+    pub fn with_max_route_distance(self, max_route_distance: i32) -> Result<Self, InstanceError> {
+        let instance = Self {
+            max_route_distance: Some(max_route_distance),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// limits the duration of every route, see [`AlkaidInstance::route_duration`].
+    pub fn with_max_route_duration(self, max_route_duration: i32) -> Result<Self, InstanceError> {
+        let instance = Self {
+            max_route_duration: Some(max_route_duration),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// sets the time spent at every visit of each customer, `service_times[i - 1]` for customer
+    /// `i`.
+    pub fn with_service_times(self, service_times: Vec<i32>) -> Result<Self, InstanceError> {
+        let instance = Self {
+            service_times: Some(service_times),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// restricts the arrival at every node to its `(earliest, latest)` window, where the window of
+    /// the depot at index `0` bounds the departure and return of every route.
+    pub fn with_time_windows(self, time_windows: Vec<(i32, i32)>) -> Result<Self, InstanceError> {
+        let instance = Self {
+            time_windows: Some(time_windows),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// limits the number of visits to each customer, `max_visits[i - 1]` for customer `i`. a
+    /// customer with a maximum of `1` receives its whole demand at once.
+    pub fn with_max_visits(self, max_visits: Vec<usize>) -> Result<Self, InstanceError> {
+        let instance = Self {
+            max_visits: Some(max_visits),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// sets the same minimum load of a visit for every customer, see [`min_delivery`].
+    pub fn with_min_delivery(self, min_delivery: i32) -> Result<Self, InstanceError> {
+        let min_deliveries = vec![min_delivery; self.demands.len()];
+        self.with_min_deliveries(min_deliveries)
+    }
+
+    /// sets the minimum load of a visit to each customer, `min_deliveries[i - 1]` for customer
+    /// `i`, see [`min_delivery`].
+    pub fn with_min_deliveries(self, min_deliveries: Vec<i32>) -> Result<Self, InstanceError> {
+        let instance = Self {
+            min_deliveries: Some(min_deliveries),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// lets every route end at its last customer instead of returning to the depot.
+    pub fn with_open_routes(self, open_routes: bool) -> Result<Self, InstanceError> {
+        let instance = Self {
+            open_routes,
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// lets the demand of every customer be left undelivered, in part or in full, at a penalty per
+    /// unit of `unserved_penalties[i - 1]` for customer `i`.
+    pub fn with_unserved_penalties(
+        self,
+        unserved_penalties: Vec<i32>,
+    ) -> Result<Self, InstanceError> {
+        let instance = Self {
+            unserved_penalties: Some(unserved_penalties),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// sets the quantity collected from each customer, `pickups[i - 1]` for customer `i`.
+    pub fn with_pickups(self, pickups: Vec<i32>) -> Result<Self, InstanceError> {
+        let instance = Self {
+            pickups: Some(pickups),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+
+    /// sets the travel times between the nodes, which are the distances otherwise.
+    pub fn with_travel_times(self, travel_times: Vec<Vec<i32>>) -> Result<Self, InstanceError> {
+        validate_matrix_shape(&travel_times, self.demands.len() + 1)?;
+        let instance = Self {
+            travel_times: Some(travel_times),
+            ..self
+        };
+        validate(&instance)?;
+        Ok(instance)
+    }
+}
+
+/// the deserialized form of [`Instance`], which is validated before it becomes an instance.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawInstance {
+    capacity: i32,
+    demands: Vec<i32>,
+    input: InputFormat,
+    #[serde(default)]
+    max_vehicles: Option<usize>,
+    #[serde(default)]
+    fleet: Option<Vec<VehicleType>>,
+    #[serde(default)]
+    max_route_distance: Option<i32>,
+    #[serde(default)]
+    max_route_duration: Option<i32>,
+    #[serde(default)]
+    service_times: Option<Vec<i32>>,
+    #[serde(default)]
+    time_windows: Option<Vec<(i32, i32)>>,
+    #[serde(default)]
+    travel_times: Option<Vec<Vec<i32>>>,
+    #[serde(default)]
+    max_visits: Option<Vec<usize>>,
+    #[serde(default)]
+    min_deliveries: Option<Vec<i32>>,
+    #[serde(default)]
+    open_routes: bool,
+    #[serde(default)]
+    unserved_penalties: Option<Vec<i32>>,
+    #[serde(default)]
+    pickups: Option<Vec<i32>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawInstance> for Instance {
+    type Error = InstanceError;
+
+    fn try_from(raw: RawInstance) -> Result<Self, Self::Error> {
+        if let Some(travel_times) = &raw.travel_times {
+            validate_matrix_shape(travel_times, raw.demands.len() + 1)?;
         }
+        let instance = Self {
+            capacity: raw.capacity,
+            demands: raw.demands,
+            input: raw.input,
+            max_vehicles: raw.max_vehicles,
+            fleet: raw.fleet,
+            max_route_distance: raw.max_route_distance,
+            max_route_duration: raw.max_route_duration,
+            service_times: raw.service_times,
+            time_windows: raw.time_windows,
+            travel_times: raw.travel_times,
+            max_visits: raw.max_visits,
+            min_deliveries: raw.min_deliveries,
+            open_routes: raw.open_routes,
+            unserved_penalties: raw.unserved_penalties,
+            pickups: raw.pickups,
+        };
+        validate(&instance)?;
+        Ok(instance)
     }
 }
 
@@ -92,4 +909,457 @@ impl AlkaidInstance for Instance {
     fn input_format(&self) -> &InputFormat {
         &self.input
     }
+
+    fn max_vehicles(&self) -> Option<usize> {
+        self.max_vehicles
+    }
+
+    fn fleet(&self) -> Option<&[VehicleType]> {
+        self.fleet.as_deref()
+    }
+
+    fn max_route_distance(&self) -> Option<i32> {
+        self.max_route_distance
+    }
+
+    fn max_route_duration(&self) -> Option<i32> {
+        self.max_route_duration
+    }
+
+    fn service_times(&self) -> Option<&[i32]> {
+        self.service_times.as_deref()
+    }
+
+    fn travel_time(&self, from: usize, to: usize) -> i32 {
+        match &self.travel_times {
+            Some(travel_times) => travel_times[from][to],
+            None => self.distance(from, to),
+        }
+    }
+
+    fn time_windows(&self) -> Option<&[(i32, i32)]> {
+        self.time_windows.as_deref()
+    }
+
+    fn max_visits(&self) -> Option<&[usize]> {
+        self.max_visits.as_deref()
+    }
+
+    fn min_deliveries(&self) -> Option<&[i32]> {
+        self.min_deliveries.as_deref()
+    }
+
+    fn open_routes(&self) -> bool {
+        self.open_routes
+    }
+
+    fn unserved_penalties(&self) -> Option<&[i32]> {
+        self.unserved_penalties.as_deref()
+    }
+
+    fn pickups(&self) -> Option<&[i32]> {
+        self.pickups.as_deref()
+    }
+}
+
+/// whether the distance between every pair of nodes is the same in both directions.
+pub fn is_symmetric<T: AlkaidInstance + ?Sized>(instance: &T) -> bool {
+    let num_nodes = instance.demands().len() + 1;
+    (0..num_nodes).all(|i| (0..i).all(|j| instance.distance(i, j) == instance.distance(j, i)))
+}
+
+/// checks that the data of `instance` is consistent before it is handed to the solver.
+pub fn validate<T: AlkaidInstance + ?Sized>(instance: &T) -> Result<(), InstanceError> {
+    if instance.capacity() <= 0 {
+        return Err(InstanceError::NonPositiveCapacity(instance.capacity()));
+    }
+    if let Some((i, &demand)) = instance
+        .demands()
+        .iter()
+        .enumerate()
+        .find(|(_, demand)| **demand <= 0)
+    {
+        return Err(InstanceError::NonPositiveDemand {
+            customer: i + 1,
+            demand,
+        });
+    }
+    if let Some(fleet) = instance.fleet() {
+        validate_fleet(fleet, instance.capacity())?;
+    }
+    let total_demand = instance.demands().iter().map(|&d| d as i64).sum::<i64>();
+    let fleet_capacities = [
+        instance
+            .max_vehicles()
+            .map(|max_vehicles| max_vehicles as i64 * instance.capacity() as i64),
+        instance.fleet().map(|fleet| {
+            fleet
+                .iter()
+                .map(|vehicle_type| vehicle_type.count as i64 * vehicle_type.capacity as i64)
+                .sum()
+        }),
+    ];
+    // demand that the fleet cannot carry may be left undelivered if it has a penalty.
+    if let Some(fleet_capacity) = fleet_capacities.into_iter().flatten().min()
+        && instance.unserved_penalties().is_none()
+        && total_demand > fleet_capacity
+    {
+        return Err(InstanceError::InsufficientFleet {
+            total_demand,
+            fleet_capacity,
+        });
+    }
+    validate_route_limits(instance)?;
+    validate_split_policy(instance)?;
+    validate_unserved_penalties(instance)?;
+    let num_nodes = instance.demands().len() + 1;
+    match instance.input_format() {
+        InputFormat::DenseMatrix(matrix) => {
+            validate_matrix_shape(matrix, num_nodes)?;
+            for (i, row) in matrix.iter().enumerate() {
+                if row[i] != 0 {
+                    return Err(InstanceError::NonZeroDiagonal {
+                        node: i,
+                        distance: row[i],
+                    });
+                }
+                if let Some((j, &distance)) = row.iter().enumerate().find(|(_, d)| **d < 0) {
+                    return Err(InstanceError::NegativeDistance {
+                        from: i,
+                        to: j,
+                        distance,
+                    });
+                }
+            }
+        }
+        #[allow(deprecated)]
+        InputFormat::CoordList(coords) => {
+            let coords = coords
+                .iter()
+                .map(|&(x, y)| (x as f64, y as f64))
+                .collect::<Vec<_>>();
+            validate_coords(&coords, DistanceRounding::Round, num_nodes)?;
+            validate_fits(DistanceRounding::Round, planar_diameter(&coords))?;
+        }
+        InputFormat::Planar { coords, rounding } => {
+            validate_coords(coords, *rounding, num_nodes)?;
+            validate_fits(*rounding, planar_diameter(coords))?;
+        }
+        InputFormat::LatLon {
+            coords,
+            metric,
+            unit,
+            rounding,
+        } => {
+            validate_coords(coords, *rounding, num_nodes)?;
+            // no two points of the sphere are further apart than half its circumference.
+            let radius = match metric {
+                GeoMetric::Haversine => 6371.0088,
+                GeoMetric::TsplibGeo => 6378.388,
+            };
+            validate_fits(*rounding, unit.convert(std::f64::consts::PI * radius))?;
+            if let Some((node, &(latitude, longitude))) = coords
+                .iter()
+                .enumerate()
+                .find(|(_, (lat, lon))| lat.abs() > 90.0 || lon.abs() > 180.0)
+            {
+                return Err(InstanceError::LatLonOutOfRange {
+                    node,
+                    latitude,
+                    longitude,
+                });
+            }
+        }
+    }
+    validate_time_windows(instance)?;
+    validate_reachable(instance)
+}
+
+/// checks that `matrix` has `num_nodes` rows of `num_nodes` columns.
+pub(crate) fn validate_matrix_shape(
+    matrix: &[Vec<i32>],
+    num_nodes: usize,
+) -> Result<(), InstanceError> {
+    if matrix.len() != num_nodes {
+        return Err(InstanceError::NodeCountMismatch {
+            expected: num_nodes,
+            found: matrix.len(),
+        });
+    }
+    for (i, row) in matrix.iter().enumerate() {
+        if row.len() != num_nodes {
+            return Err(InstanceError::RowLengthMismatch {
+                row: i,
+                expected: num_nodes,
+                found: row.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn validate_coords(
+    coords: &[(f64, f64)],
+    rounding: DistanceRounding,
+    num_nodes: usize,
+) -> Result<(), InstanceError> {
+    if coords.len() != num_nodes {
+        return Err(InstanceError::NodeCountMismatch {
+            expected: num_nodes,
+            found: coords.len(),
+        });
+    }
+    if let Some(node) = coords
+        .iter()
+        .position(|(x, y)| !x.is_finite() || !y.is_finite())
+    {
+        return Err(InstanceError::NonFiniteCoordinate { node });
+    }
+    if let DistanceRounding::Scaled(factor) = rounding
+        && !(factor.is_finite() && factor > 0.0)
+    {
+        return Err(InstanceError::InvalidScale(factor));
+    }
+    Ok(())
+}
+
+/// an upper bound of the distance of any two of `coords`: the diagonal of their bounding box.
+fn planar_diameter(coords: &[(f64, f64)]) -> f64 {
+    let (min, max) = coords.iter().fold(
+        (
+            (f64::INFINITY, f64::INFINITY),
+            (f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), &(x, y)| ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y))),
+    );
+    if coords.is_empty() {
+        0.0
+    } else {
+        (max.0 - min.0).hypot(max.1 - min.1)
+    }
+}
+
+/// checks that distances up to `max_distance` are not saturated by `rounding`.
+fn validate_fits(rounding: DistanceRounding, max_distance: f64) -> Result<(), InstanceError> {
+    if rounding.fits(max_distance) {
+        Ok(())
+    } else {
+        Err(InstanceError::DistanceOverflow {
+            distance: max_distance,
+        })
+    }
+}
+
+fn validate_fleet(fleet: &[VehicleType], capacity: i32) -> Result<(), InstanceError> {
+    if fleet.is_empty() {
+        return Err(InstanceError::EmptyFleet);
+    }
+    for (i, vehicle_type) in fleet.iter().enumerate() {
+        if vehicle_type.capacity <= 0 {
+            return Err(InstanceError::NonPositiveCapacity(vehicle_type.capacity));
+        }
+        if vehicle_type.fixed_cost < 0 {
+            return Err(InstanceError::NegativeFixedCost {
+                vehicle_type: i,
+                fixed_cost: vehicle_type.fixed_cost,
+            });
+        }
+        if !(vehicle_type.distance_multiplier.is_finite()
+            && vehicle_type.distance_multiplier >= 0.0)
+        {
+            return Err(InstanceError::InvalidDistanceMultiplier {
+                vehicle_type: i,
+                distance_multiplier: vehicle_type.distance_multiplier,
+            });
+        }
+    }
+    let largest = fleet
+        .iter()
+        .map(|vehicle_type| vehicle_type.capacity)
+        .max()
+        .unwrap_or(0);
+    if capacity != largest {
+        return Err(InstanceError::FleetCapacityMismatch { capacity, largest });
+    }
+    Ok(())
+}
+
+/// checks the service times and the maximum distance and duration of a route.
+fn validate_route_limits<T: AlkaidInstance + ?Sized>(instance: &T) -> Result<(), InstanceError> {
+    let num_customers = instance.demands().len();
+    if let Some(service_times) = instance.service_times() {
+        if service_times.len() != num_customers {
+            return Err(InstanceError::ServiceTimeCountMismatch {
+                expected: num_customers,
+                found: service_times.len(),
+            });
+        }
+        if let Some((i, &service_time)) = service_times.iter().enumerate().find(|(_, t)| **t < 0) {
+            return Err(InstanceError::NegativeServiceTime {
+                customer: i + 1,
+                service_time,
+            });
+        }
+    }
+    for limit in [instance.max_route_distance(), instance.max_route_duration()]
+        .into_iter()
+        .flatten()
+    {
+        if limit <= 0 {
+            return Err(InstanceError::NonPositiveRouteLimit(limit));
+        }
+    }
+    Ok(())
+}
+
+fn validate_unserved_penalties<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+) -> Result<(), InstanceError> {
+    let Some(unserved_penalties) = instance.unserved_penalties() else {
+        return Ok(());
+    };
+    let num_customers = instance.demands().len();
+    if unserved_penalties.len() != num_customers {
+        return Err(InstanceError::UnservedPenaltyCountMismatch {
+            expected: num_customers,
+            found: unserved_penalties.len(),
+        });
+    }
+    if let Some((i, &penalty)) = unserved_penalties.iter().enumerate().find(|(_, p)| **p < 0) {
+        return Err(InstanceError::NegativeUnservedPenalty {
+            customer: i + 1,
+            penalty,
+        });
+    }
+    Ok(())
+}
+
+/// checks that the demand of every customer can be delivered within its maximum number of visits,
+/// by visits of at least its minimum load and at most the capacity.
+fn validate_split_policy<T: AlkaidInstance + ?Sized>(instance: &T) -> Result<(), InstanceError> {
+    let num_customers = instance.demands().len();
+    if let Some(max_visits) = instance.max_visits() {
+        if max_visits.len() != num_customers {
+            return Err(InstanceError::MaxVisitsCountMismatch {
+                expected: num_customers,
+                found: max_visits.len(),
+            });
+        }
+        if let Some(i) = max_visits.iter().position(|&visits| visits == 0) {
+            return Err(InstanceError::ZeroMaxVisits { customer: i + 1 });
+        }
+    }
+    if let Some(min_deliveries) = instance.min_deliveries() {
+        if min_deliveries.len() != num_customers {
+            return Err(InstanceError::MinDeliveryCountMismatch {
+                expected: num_customers,
+                found: min_deliveries.len(),
+            });
+        }
+        if let Some((i, &min_delivery)) = min_deliveries.iter().enumerate().find(|(_, d)| **d <= 0)
+        {
+            return Err(InstanceError::NonPositiveMinDelivery {
+                customer: i + 1,
+                min_delivery,
+            });
+        }
+    }
+    if let Some(pickups) = instance.pickups() {
+        if pickups.len() != num_customers {
+            return Err(InstanceError::PickupCountMismatch {
+                expected: num_customers,
+                found: pickups.len(),
+            });
+        }
+        if let Some((i, &pickup)) = pickups
+            .iter()
+            .enumerate()
+            .find(|(_, p)| **p < 0 || **p > instance.capacity())
+        {
+            return Err(InstanceError::InvalidPickup {
+                customer: i + 1,
+                pickup,
+            });
+        }
+    }
+    for customer in 1..=num_customers {
+        if max_split(instance, customer) < min_split(instance, instance.capacity(), customer) {
+            return Err(InstanceError::UnsplittableDemand { customer });
+        }
+    }
+    Ok(())
+}
+
+/// the fewest visits that deliver the demand of `customer` with vehicles of `capacity`.
+pub(crate) fn min_split<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    capacity: i32,
+    customer: usize,
+) -> usize {
+    (instance.demands()[customer - 1] as usize).div_ceil(capacity as usize)
+}
+
+/// the most visits allowed to deliver the demand of `customer`, limited by its
+/// [`visit_limit`] and its minimum load.
+pub(crate) fn max_split<T: AlkaidInstance + ?Sized>(instance: &T, customer: usize) -> usize {
+    let by_load = (instance.demands()[customer - 1] / min_delivery(instance, customer)) as usize;
+    visit_limit(instance, customer).map_or(by_load, |max_visits| max_visits.min(by_load))
+}
+
+/// checks the travel times and that there is one non-empty time window per node.
+fn validate_time_windows<T: AlkaidInstance + ?Sized>(instance: &T) -> Result<(), InstanceError> {
+    let num_nodes = instance.demands().len() + 1;
+    for from in 0..num_nodes {
+        for to in 0..num_nodes {
+            let travel_time = instance.travel_time(from, to);
+            if travel_time < 0 {
+                return Err(InstanceError::NegativeTravelTime {
+                    from,
+                    to,
+                    travel_time,
+                });
+            }
+        }
+    }
+    let Some(time_windows) = instance.time_windows() else {
+        return Ok(());
+    };
+    if time_windows.len() != num_nodes {
+        return Err(InstanceError::TimeWindowCountMismatch {
+            expected: num_nodes,
+            found: time_windows.len(),
+        });
+    }
+    if let Some((node, &(earliest, latest))) = time_windows
+        .iter()
+        .enumerate()
+        .find(|(_, (earliest, latest))| earliest > latest)
+    {
+        return Err(InstanceError::InvalidTimeWindow {
+            node,
+            earliest,
+            latest,
+        });
+    }
+    Ok(())
+}
+
+/// checks that every customer can be served by a route of its own within the route limits and
+/// the time windows.
+fn validate_reachable<T: AlkaidInstance + ?Sized>(instance: &T) -> Result<(), InstanceError> {
+    for customer in 1..=instance.demands().len() {
+        let distance = route_distance(instance, &[customer]);
+        let duration = instance.route_duration(&[customer]);
+        if instance
+            .max_route_distance()
+            .is_some_and(|max| distance > max)
+            || instance
+                .max_route_duration()
+                .is_some_and(|max| duration > max)
+            || !is_on_time(instance, &[customer])
+        {
+            return Err(InstanceError::UnreachableCustomer { customer });
+        }
+    }
+    Ok(())
 }
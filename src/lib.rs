@@ -1,8 +1,49 @@
+pub mod cancellation;
 pub mod config;
+pub mod depot;
+pub mod error;
+pub mod feasibility;
+pub mod fleet;
 pub mod instance;
+mod limits;
+pub mod listener;
+mod moves;
+mod policy;
+mod prizes;
+mod sol;
+pub mod solution;
+pub mod vrplib;
+
+pub use cancellation::CancellationToken;
+pub use depot::{Depot, MultiDepotInstance};
+pub use error::{InstanceError, ParseError, SolveError};
+pub use feasibility::{Violation, check_feasibility};
+pub use fleet::VehicleType;
+pub use listener::{PrintListener, SolveListener};
+pub use solution::{Route, Solution};
+
+use depot::DepotListener;
+use listener::ListenerAdapter;
 
 #[cxx::bridge(namespace = "alkaidsd")]
 pub mod ffi {
+    /// the raw output of the solver.
+    struct SolveResult {
+        /// whether the search found a solution, it may be stopped before the first one.
+        found: bool,
+        /// the routes encoded as expected by `split_results`.
+        routes: Vec<i32>,
+    }
+
+    extern "Rust" {
+        type ListenerAdapter<'a>;
+
+        fn on_start(self: &mut ListenerAdapter<'_>);
+        fn on_updated(self: &mut ListenerAdapter<'_>, routes: Vec<i32>, objective: i32);
+        fn should_stop(self: &mut ListenerAdapter<'_>) -> bool;
+        fn progress(self: &ListenerAdapter<'_>) -> f64;
+    }
+
     unsafe extern "C++" {
         include!("sdvrp/src/AlkaidSDVRP.h");
 
@@ -27,11 +68,9 @@ pub mod ffi {
 
             capacity: i32,
             demands: Vec<i32>,
-            input_format: &str,
             distance_matrix: Vec<i32>,
-            coord_list_x: Vec<i32>,
-            coord_list_y: Vec<i32>,
-        ) -> Vec<i32>;
+            listener: &mut ListenerAdapter<'_>,
+        ) -> Result<SolveResult>;
     }
 }
 
@@ -65,10 +104,248 @@ pub fn split_results(results: Vec<i32>) -> Vec<Vec<(i32, i32)>> {
     return routes;
 }
 
+/// the distances the solver receives, computed once per solve: the distances of
+/// [`AlkaidInstance::distance`](instance::AlkaidInstance::distance), so that its routes cost the
+/// same as in [`Solution::cost`], where every arc back to the depot is free with
+/// [`open_routes`](instance::AlkaidInstance::open_routes).
+struct Distances {
+    num_nodes: usize,
+    matrix: Vec<i32>,
+}
+
+impl Distances {
+    fn new<T: instance::AlkaidInstance + ?Sized>(instance: &T) -> Self {
+        let num_nodes = instance.demands().len() + 1;
+        let matrix = instance::dense_matrix(num_nodes, |i, j| {
+            if j == 0 && instance.open_routes() {
+                0
+            } else {
+                instance.distance(i, j)
+            }
+        });
+        Self { num_nodes, matrix }
+    }
+
+    fn is_symmetric(&self) -> bool {
+        let n = self.num_nodes;
+        (0..n).all(|i| (0..i).all(|j| self.matrix[i * n + j] == self.matrix[j * n + i]))
+    }
+
+    /// a penalty per depot arc that makes the search prefer fewer routes: the longest arc of the
+    /// instance, which an additional route rarely saves. it is capped so that no arc overflows
+    /// with the penalty.
+    fn vehicle_penalty(&self) -> i32 {
+        let longest = self.matrix.iter().copied().max().unwrap_or(0);
+        longest.max(1).min(i32::MAX - longest)
+    }
+
+    /// the distance matrix in row-major order, with `vehicle_penalty` added to every arc from and
+    /// to the depot, so that every route costs twice the penalty on top of its distance.
+    fn with_vehicle_penalty(&self, vehicle_penalty: i32) -> Vec<i32> {
+        let n = self.num_nodes;
+        let mut matrix = self.matrix.clone();
+        for i in 1..n {
+            matrix[i] += vehicle_penalty;
+            matrix[i * n] += vehicle_penalty;
+        }
+        matrix
+    }
+}
+
 pub fn solve_sdvrp<T: config::AlkaidConfig, T2: instance::AlkaidInstance>(
     config: &T,
     instance: &T2,
-) -> Vec<Vec<(i32, i32)>> {
+) -> Result<Solution, SolveError> {
+    solve_sdvrp_with_listener(config, instance, &mut ())
+}
+
+/// same as [`solve_sdvrp`], but reports the progress of the search to `listener`.
+///
+/// the search itself does not limit the number of routes. if the instance has a
+/// [`max_vehicles`](instance::AlkaidInstance::max_vehicles) that a solution might exceed, the
+/// search runs with a penalty on every route. if the fleet is still exceeded,
+/// [`SolveError::FleetExceeded`] is returned with the solution found.
+///
+/// if the distances the solver sees are asymmetric, the operators that
+/// [`assume symmetry`](config::InterOperators::assumes_symmetry) are left out of the search, and
+/// the objective is always the cost of the routes rather than the solver's own bookkeeping. if
+/// that leaves no inter or no intra operator, [`SolveError::InvalidConfig`] is returned.
+///
+/// the search ignores travel times, time windows and pickups. routes of the search that exceed the
+/// maximum distance or duration of a route, miss a time window or exceed the capacity with the
+/// [`pickups`](instance::AlkaidInstance::pickups) on board are reordered afterwards, and split
+/// into several routes if reordering is not enough, before the fleet size is checked. likewise,
+/// the split-aware operators and the split reinsertion of the search split every demand freely,
+/// so customers whose visits break their [`max_visits`](instance::AlkaidInstance::max_visits) or
+/// [`min_deliveries`](instance::AlkaidInstance::min_deliveries), or customers with a pickup that
+/// are visited more than once, are delivered again afterwards.
+///
+/// the search delivers every demand. with
+/// [`unserved_penalties`](instance::AlkaidInstance::unserved_penalties), visits whose detour costs
+/// more than their penalty are removed afterwards, unless the customer has a pickup, and a fleet
+/// that is still exceeded drops the routes whose loads are worth the least instead of failing,
+/// then serves the dropped demand in part where the kept routes have room for it. the objective then includes the penalty of the
+/// [`unserved`](Solution::unserved) demand.
+///
+/// [`on_updated`](SolveListener::on_updated) reports the routes of the search before any of this
+/// post-processing, which deliver every demand, while [`on_end`](SolveListener::on_end) receives
+/// the returned solution and its objective.
+///
+/// the search also knows a single capacity and minimizes the distance. with a heterogeneous
+/// [`fleet`](instance::AlkaidInstance::fleet), it uses the largest capacity, with a penalty on
+/// every route if the fleet might be exceeded, then fits the routes to the vehicles with
+/// [`fleet::fit_fleet`]. the objective is then the [`fleet_cost`](Solution::fleet_cost) of the
+/// routes, with the fixed costs and distance multipliers of their vehicle types. if some load
+/// cannot be delivered by the fleet, [`SolveError::FleetAssignment`] is returned.
+pub fn solve_sdvrp_with_listener<
+    T: config::AlkaidConfig,
+    T2: instance::AlkaidInstance,
+    L: SolveListener,
+>(
+    config: &T,
+    instance: &T2,
+    listener: &mut L,
+) -> Result<Solution, SolveError> {
+    let solution = solve_instance(config, instance, listener)?;
+    listener.on_end(&solution, solution.objective);
+    Ok(solution)
+}
+
+/// [`solve_sdvrp_with_listener`] without the final [`on_end`](SolveListener::on_end), for callers
+/// that may return another solution.
+fn solve_instance<T: config::AlkaidConfig, T2: instance::AlkaidInstance, L: SolveListener>(
+    config: &T,
+    instance: &T2,
+    listener: &mut L,
+) -> Result<Solution, SolveError> {
+    config::validate(config).map_err(SolveError::InvalidConfig)?;
+    instance::validate(instance)?;
+    let distances = Distances::new(instance);
+    let mut config = config::Adjusted::new(config);
+    // free arcs back to the depot make the distances the solver sees asymmetric.
+    if !distances.is_symmetric() {
+        config = config
+            .without_symmetric_operators()
+            .map_err(SolveError::InvalidConfig)?;
+    }
+    let config = &config;
+    let Some(fleet) = instance.fleet() else {
+        return solve_with_max_vehicles(
+            config,
+            instance,
+            &distances,
+            listener,
+            instance.capacity(),
+        );
+    };
+    // routes of the largest capacity are fitted to the fleet afterwards.
+    let capacity = fleet
+        .iter()
+        .map(|vehicle_type| vehicle_type.capacity)
+        .max()
+        .unwrap_or(instance.capacity());
+    let num_vehicles = fleet
+        .iter()
+        .map(|vehicle_type| vehicle_type.count)
+        .sum::<usize>();
+    let vehicle_penalty = vehicle_penalty(instance, &distances, capacity, num_vehicles);
+    let solution = solve(
+        config,
+        instance,
+        &distances,
+        listener,
+        capacity,
+        vehicle_penalty,
+    )?;
+    let solution = fleet::fit_fleet(instance, solution).ok_or(SolveError::FleetAssignment)?;
+    limit_vehicles(instance, solution)
+}
+
+/// runs the solver with vehicles of `capacity`, with a vehicle penalty if the instance has a
+/// [`max_vehicles`](instance::AlkaidInstance::max_vehicles) that the solution might exceed.
+fn solve_with_max_vehicles<
+    T: config::AlkaidConfig,
+    T2: instance::AlkaidInstance,
+    L: SolveListener,
+>(
+    config: &T,
+    instance: &T2,
+    distances: &Distances,
+    listener: &mut L,
+    capacity: i32,
+) -> Result<Solution, SolveError> {
+    let vehicle_penalty = instance.max_vehicles().map_or(0, |max_vehicles| {
+        vehicle_penalty(instance, distances, capacity, max_vehicles)
+    });
+    let solution = solve(
+        config,
+        instance,
+        distances,
+        listener,
+        capacity,
+        vehicle_penalty,
+    )?;
+    limit_vehicles(instance, solution)
+}
+
+/// the vehicle penalty if `num_vehicles` are fewer than the visits needed to deliver every demand
+/// with vehicles of `capacity` when each visit fills a vehicle, which is the most visits a route
+/// of a single customer needs, or else `0`.
+fn vehicle_penalty<T: instance::AlkaidInstance>(
+    instance: &T,
+    distances: &Distances,
+    capacity: i32,
+    num_vehicles: usize,
+) -> i32 {
+    let visits = instance
+        .demands()
+        .iter()
+        .map(|&demand| (demand as usize).div_ceil(capacity as usize))
+        .sum::<usize>();
+    if num_vehicles < visits {
+        distances.vehicle_penalty()
+    } else {
+        0
+    }
+}
+
+/// checks `solution` against the [`max_vehicles`](instance::AlkaidInstance::max_vehicles) of the
+/// instance, dropping the routes worth the least if the instance has unserved penalties.
+fn limit_vehicles<T: instance::AlkaidInstance>(
+    instance: &T,
+    solution: Solution,
+) -> Result<Solution, SolveError> {
+    match instance.max_vehicles() {
+        Some(max_vehicles) if solution.num_vehicles() > max_vehicles => {
+            if instance.unserved_penalties().is_some() {
+                return Ok(prizes::drop_routes(instance, solution, max_vehicles));
+            }
+            Err(SolveError::FleetExceeded {
+                vehicles: solution.num_vehicles(),
+                max_vehicles,
+                solution: Box::new(solution),
+            })
+        }
+        _ => Ok(solution),
+    }
+}
+
+/// runs the solver once on validated input with vehicles of `capacity`, see
+/// [`Distances::with_vehicle_penalty`] for `vehicle_penalty`.
+fn solve<T: config::AlkaidConfig, T2: instance::AlkaidInstance, L: SolveListener>(
+    config: &T,
+    instance: &T2,
+    distances: &Distances,
+    listener: &mut L,
+    capacity: i32,
+    vehicle_penalty: i32,
+) -> Result<Solution, SolveError> {
+    let mut adapter = ListenerAdapter::new(
+        instance,
+        config.time_limit(),
+        config.termination(),
+        listener,
+    );
     let result = unsafe {
         ffi::solve_sdvrp(
             config.random_seed(),
@@ -96,13 +373,114 @@ pub fn solve_sdvrp<T: config::AlkaidConfig, T2: instance::AlkaidInstance>(
             config.ruin_method_type().to_random_ruin_sizes(),
             config.sorters().iter().map(|(e, _)| e.to_str()).collect(),
             config.sorters().iter().map(|(_, e)| *e).collect(),
-            instance.capacity(),
+            capacity,
             instance.demands().to_vec(),
-            instance.input_format().to_str(),
-            instance.input_format().to_dense_matrix(),
-            instance.input_format().to_coord_list_x(),
-            instance.input_format().to_coord_list_y(),
+            distances.with_vehicle_penalty(vehicle_penalty),
+            &mut adapter,
         )
     };
-    return split_results(result);
+    adapter.resume_panic();
+    let result = result?;
+    if !result.found {
+        return Err(SolveError::Cancelled);
+    }
+    let solution = adapter.decode(result.routes);
+    let solution = limits::split_long_routes(instance, solution, capacity);
+    let solution = policy::repair_split_policy(instance, solution, capacity);
+    Ok(prizes::drop_unprofitable_visits(instance, solution))
+}
+
+/// solves a multi-depot instance, cluster first, route second: the demands are assigned to the
+/// depots with [`MultiDepotInstance::assign_customers`], then the customers of every depot are
+/// solved as a single-depot instance with the fleet limit of the depot, see
+/// [`solve_sdvrp_with_listener`]. the solver knows a single depot, node `0`, so it cannot move
+/// customers between depots.
+///
+/// the time limit of `config` is shared between the depots in proportion to the demand they
+/// deliver, and `listener` sees one search per depot that serves customers. the routes, also
+/// those reported to `listener` and those of [`SolveError::FleetExceeded`], keep the customer
+/// numbers of `instance` and carry the index of their [`depot`](Route::depot). the solution of
+/// [`SolveError::FleetExceeded`] holds the routes of the depot whose fleet is exceeded.
+pub fn solve_multi_depot<T: config::AlkaidConfig, L: SolveListener>(
+    config: &T,
+    instance: &MultiDepotInstance,
+    listener: &mut L,
+) -> Result<Solution, SolveError> {
+    let assigned = instance.assign_customers();
+    let total_demand = instance
+        .demands()
+        .iter()
+        .map(|&demand| demand as f64)
+        .sum::<f64>();
+    let mut routes = vec![];
+    for (depot, parts) in assigned.into_iter().enumerate() {
+        if parts.is_empty() {
+            continue;
+        }
+        let mut sub_instance = instance.sub_instance(depot, &parts)?;
+        if let Some(max_vehicles) = instance.depots()[depot].max_vehicles {
+            sub_instance = sub_instance.with_max_vehicles(max_vehicles)?;
+        }
+        let demand = parts
+            .iter()
+            .map(|&(_, quantity)| quantity as f64)
+            .sum::<f64>();
+        let mut depot_config = config::Adjusted::new(config);
+        depot_config.time_limit *= demand / total_demand;
+        let mut depot_listener = DepotListener::new(instance, depot, &parts, &mut *listener);
+        let solution = solve_sdvrp_with_listener(&depot_config, &sub_instance, &mut depot_listener)
+            .map_err(|e| depot_listener.error(e))?;
+        routes.extend(depot_listener.solution(&solution).routes);
+    }
+    let objective = routes.iter().map(|route| route.distance).sum();
+    Ok(Solution { routes, objective })
+}
+
+/// same as [`solve_sdvrp_with_listener`], but starts from an existing route plan in the shape
+/// returned by [`split_results`], e.g. yesterday's or a manually edited plan.
+///
+/// the plan is checked with [`check_feasibility`] first, and returned as
+/// [`SolveError::InvalidInitialSolution`] with the violations if it does not fit the instance.
+/// with a heterogeneous [`fleet`](instance::AlkaidInstance::fleet), its routes are assigned
+/// vehicle types with [`fleet::assign_vehicle_types`], or [`SolveError::FleetAssignment`] is
+/// returned.
+///
+/// `AlkaidSolver::Solve` has no entry point for a starting solution, so the search still
+/// constructs its own and the plan serves as the incumbent: the plan is returned unless the
+/// search finds a strictly better solution, and also if the search is stopped before it found a
+/// solution or exceeds the fleet.
+pub fn solve_sdvrp_with_initial_solution<
+    T: config::AlkaidConfig,
+    T2: instance::AlkaidInstance,
+    L: SolveListener,
+>(
+    config: &T,
+    instance: &T2,
+    initial_solution: &[Vec<(i32, i32)>],
+    listener: &mut L,
+) -> Result<Solution, SolveError> {
+    instance::validate(instance)?;
+    let violations = check_feasibility(instance, initial_solution);
+    if !violations.is_empty() {
+        return Err(SolveError::InvalidInitialSolution(violations));
+    }
+    let mut initial = Solution::new(instance, initial_solution.to_vec(), 0);
+    if let Some(fleet) = instance.fleet() {
+        let vehicle_types = fleet::assign_vehicle_types(fleet, &initial.routes)
+            .ok_or(SolveError::FleetAssignment)?;
+        for (route, vehicle_type) in initial.routes.iter_mut().zip(vehicle_types) {
+            route.vehicle_type = Some(vehicle_type);
+        }
+    }
+    initial.objective = initial.penalized_cost(instance);
+    let solution = match solve_instance(config, instance, listener) {
+        Ok(solution) if solution.objective < initial.objective => solution,
+        Ok(_)
+        | Err(
+            SolveError::Cancelled | SolveError::FleetExceeded { .. } | SolveError::FleetAssignment,
+        ) => initial,
+        Err(e) => return Err(e),
+    };
+    listener.on_end(&solution, solution.objective);
+    Ok(solution)
 }
@@ -0,0 +1,220 @@
+use crate::instance::{
+    AlkaidInstance, is_on_time, route_distance, route_peak_load, route_schedule,
+};
+use crate::moves::customers;
+use crate::solution::Solution;
+
+/// whether a route visiting `customers` respects the maximum distance and duration of a route and
+/// the time windows.
+pub(crate) fn within_limits<T: AlkaidInstance + ?Sized>(instance: &T, customers: &[usize]) -> bool {
+    instance
+        .max_route_distance()
+        .is_none_or(|max| route_distance(instance, customers) <= max)
+        && instance
+            .max_route_duration()
+            .is_none_or(|max| instance.route_duration(customers) <= max)
+        && is_on_time(instance, customers)
+}
+
+/// reorders, and if that is not enough splits, every route of `solution` that exceeds the maximum
+/// distance or duration of a route, misses a time window or exceeds `capacity` with its pickups on
+/// board. the visits of such a route are first reordered to respect the limits with as little
+/// distance as possible, then cut into consecutive parts that respect them.
+///
+/// the search only knows the capacity and the deliveries, so its routes may exceed the limits. [`instance::validate`]
+/// ensures that every customer fits a route of its own, so a split always exists.
+///
+/// [`instance::validate`]: crate::instance::validate
+pub(crate) fn split_long_routes<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    solution: Solution,
+    capacity: i32,
+) -> Solution {
+    let fits = |visits: &[(i32, i32)]| excess(instance, visits, capacity) == 0;
+    if solution.routes.iter().all(|route| fits(&route.visits)) {
+        return solution;
+    }
+    let routes = solution
+        .routes
+        .iter()
+        .flat_map(|route| {
+            if fits(&route.visits) {
+                return vec![route.visits.clone()];
+            }
+            let visits = reorder(instance, route.visits.clone(), capacity);
+            split_route(instance, &visits, &customers(&visits), capacity)
+        })
+        .collect();
+    let mut solution = Solution::new(instance, routes, 0);
+    solution.objective = solution.cost();
+    solution
+}
+
+/// how far a route of `visits` exceeds `capacity` and the maximum distance and duration of a
+/// route, and how late it arrives at its customers and back at the depot, summed up. `0` if and
+/// only if the route is within them.
+fn excess<T: AlkaidInstance + ?Sized>(instance: &T, visits: &[(i32, i32)], capacity: i32) -> i64 {
+    let customers = customers(visits);
+    let over = |value: i32, max: Option<i32>| max.map_or(0, |max| (value - max).max(0) as i64);
+    let lateness = instance.time_windows().map_or(0, |time_windows| {
+        let latest = |node: usize| time_windows.get(node).map(|&(_, latest)| latest);
+        let schedule = route_schedule(instance, &customers);
+        customers
+            .iter()
+            .zip(&schedule.arrivals)
+            .map(|(&customer, &arrival)| over(arrival, latest(customer)))
+            .sum::<i64>()
+            + over(schedule.return_time, latest(0))
+    });
+    lateness
+        + over(route_peak_load(instance, visits), Some(capacity))
+        + over(
+            route_distance(instance, &customers),
+            instance.max_route_distance(),
+        )
+        + over(
+            instance.route_duration(&customers),
+            instance.max_route_duration(),
+        )
+}
+
+/// moves single visits of the route to other positions as long as that lowers its
+/// [`excess`] or, at the same excess, its distance.
+fn reorder<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    mut visits: Vec<(i32, i32)>,
+    capacity: i32,
+) -> Vec<(i32, i32)> {
+    let cost = |visits: &[(i32, i32)]| {
+        (
+            excess(instance, visits, capacity),
+            route_distance(instance, &customers(visits)),
+        )
+    };
+    let mut best = cost(&visits);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for from in 0..visits.len() {
+            for to in 0..visits.len() {
+                if from == to {
+                    continue;
+                }
+                let mut moved = visits.clone();
+                let visit = moved.remove(from);
+                moved.insert(to, visit);
+                let moved_cost = cost(&moved);
+                if moved_cost < best {
+                    visits = moved;
+                    best = moved_cost;
+                    improved = true;
+                }
+            }
+        }
+    }
+    visits
+}
+
+/// cuts `visits` into consecutive parts within the route limits and `capacity`, minimizing their
+/// total distance.
+fn split_route<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    visits: &[(i32, i32)],
+    customers: &[usize],
+    capacity: i32,
+) -> Vec<Vec<(i32, i32)>> {
+    // `best[k]` is the smallest distance serving the first `k` visits, whose last part starts
+    // at `start[k]`.
+    let mut best = vec![None; visits.len() + 1];
+    let mut start = vec![0; visits.len() + 1];
+    best[0] = Some(0i64);
+    for k in 1..=visits.len() {
+        for j in 0..k {
+            let Some(before) = best[j] else {
+                continue;
+            };
+            if route_peak_load(instance, &visits[j..k]) > capacity
+                || !within_limits(instance, &customers[j..k])
+            {
+                continue;
+            }
+            let distance = before + route_distance(instance, &customers[j..k]) as i64;
+            if best[k].is_none_or(|best| distance < best) {
+                best[k] = Some(distance);
+                start[k] = j;
+            }
+        }
+    }
+    let mut parts = vec![];
+    let mut end = visits.len();
+    while end > 0 {
+        parts.push(visits[start[end]..end].to_vec());
+        end = start[end];
+    }
+    parts.reverse();
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::Instance;
+
+    fn instance() -> Instance {
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+            .with_service_times(vec![2, 3, 4])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_route_limits() {
+        let instance = instance()
+            .with_max_route_distance(21)
+            .unwrap()
+            .with_max_route_duration(28)
+            .unwrap();
+        let solution = Solution::new(
+            &instance,
+            vec![vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]],
+            0,
+        );
+        // no order of the second route is short enough, so it is cut in two.
+        let solution = split_long_routes(&instance, solution, 10);
+        assert_eq!(
+            solution.to_routes(),
+            vec![vec![(1, 6), (2, 4)], vec![(2, 4)], vec![(3, 4)]]
+        );
+        assert!(solution.violations(&instance).is_empty());
+        assert_eq!(solution.objective, 20 + 20 + 10);
+    }
+
+    #[test]
+    fn test_time_windows() {
+        let instance = instance()
+            .with_time_windows(vec![(0, 29), (10, 20), (0, 15), (0, 100)])
+            .unwrap();
+        let solution = Solution::new(
+            &instance,
+            vec![vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]],
+            0,
+        );
+        // customer 2 is served on time if it comes first.
+        let solution = split_long_routes(&instance, solution, 10);
+        assert_eq!(
+            solution.to_routes(),
+            vec![vec![(2, 4), (1, 6)], vec![(2, 4), (3, 4)]]
+        );
+        assert!(solution.violations(&instance).is_empty());
+    }
+
+    #[test]
+    fn test_pickups() {
+        let instance = Instance::from_coord_list(10, vec![6, 4], vec![(0, 0), (10, 0), (20, 0)])
+            .with_pickups(vec![8, 0])
+            .unwrap();
+        let solution = Solution::new(&instance, vec![vec![(1, 6), (2, 4)]], 0);
+        let solution = split_long_routes(&instance, solution, 10);
+        assert_eq!(solution.to_routes(), vec![vec![(2, 4), (1, 6)]]);
+        assert_eq!(solution.objective, 40);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::config::Termination;
+use crate::instance::AlkaidInstance;
+use crate::solution::Solution;
+use crate::split_results;
+use std::any::Any;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::time::Instant;
+
+/// receives progress notifications while the solver is running.
+///
+/// every method has an empty default implementation, so only the events of interest need to be handled.
+pub trait SolveListener {
+    /// called once before the search starts.
+    fn on_start(&mut self) {}
+    /// called whenever the search finds a new best solution.
+    fn on_updated(&mut self, _solution: &Solution, _objective: i32) {}
+    /// called once with the returned solution and its objective, after the routes of the search
+    /// are post-processed. not called if solving fails.
+    fn on_end(&mut self, _solution: &Solution, _objective: i32) {}
+    /// polled once per iteration of the search, returning `true` ends the search early
+    /// with the best solution found so far, or with [`SolveError::Cancelled`] if there is none
+    /// yet.
+    ///
+    /// [`SolveError::Cancelled`]: crate::SolveError::Cancelled
+    fn should_stop(&mut self) -> bool {
+        false
+    }
+}
+
+/// a listener that ignores every notification.
+impl SolveListener for () {}
+
+impl<L: SolveListener + ?Sized> SolveListener for &mut L {
+    fn on_start(&mut self) {
+        (**self).on_start()
+    }
+
+    fn on_updated(&mut self, solution: &Solution, objective: i32) {
+        (**self).on_updated(solution, objective)
+    }
+
+    fn on_end(&mut self, solution: &Solution, objective: i32) {
+        (**self).on_end(solution, objective)
+    }
+
+    fn should_stop(&mut self) -> bool {
+        (**self).should_stop()
+    }
+}
+
+/// notifies both listeners, the search stops as soon as either of them asks to.
+impl<A: SolveListener, B: SolveListener> SolveListener for (A, B) {
+    fn on_start(&mut self) {
+        self.0.on_start();
+        self.1.on_start();
+    }
+
+    fn on_updated(&mut self, solution: &Solution, objective: i32) {
+        self.0.on_updated(solution, objective);
+        self.1.on_updated(solution, objective);
+    }
+
+    fn on_end(&mut self, solution: &Solution, objective: i32) {
+        self.0.on_end(solution, objective);
+        self.1.on_end(solution, objective);
+    }
+
+    fn should_stop(&mut self) -> bool {
+        self.0.should_stop() || self.1.should_stop()
+    }
+}
+
+/// a listener that prints the elapsed time and objective of every update to stdout, or to
+/// stderr if created with [`PrintListener::stderr`].
+#[derive(Default)]
+pub struct PrintListener {
+    start_time: Option<Instant>,
+    stderr: bool,
+}
+
+impl PrintListener {
+    /// a listener that prints to stderr, e.g. to keep stdout for the solution.
+    pub fn stderr() -> Self {
+        Self {
+            start_time: None,
+            stderr: true,
+        }
+    }
+
+    fn print(&self, message: std::fmt::Arguments) {
+        if self.stderr {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    fn elapsed(&self) -> f64 {
+        self.start_time
+            .map_or(0.0, |start_time| start_time.elapsed().as_secs_f64())
+    }
+}
+
+impl SolveListener for PrintListener {
+    fn on_start(&mut self) {
+        self.start_time = Some(Instant::now());
+    }
+
+    fn on_updated(&mut self, _solution: &Solution, objective: i32) {
+        self.print(format_args!("Update at {}s: {}", self.elapsed(), objective));
+    }
+
+    fn on_end(&mut self, _solution: &Solution, objective: i32) {
+        self.print(format_args!("End at {}s: {}", self.elapsed(), objective));
+    }
+}
+
+/// forwards the callbacks of the C++ listener to a [`SolveListener`]
+/// and decides when the search is ended by a [`Termination`] criterion.
+///
+/// a panic must not unwind into the C++ solver, so a panic of the listener is caught, stops the
+/// search and is resumed on the calling thread once the solver has returned.
+pub struct ListenerAdapter<'a> {
+    instance: &'a dyn AlkaidInstance,
+    listener: &'a mut dyn SolveListener,
+    termination: Termination,
+    time_limit: f64,
+    iterations: u64,
+    last_improvement: u64,
+    best_objective: Option<i32>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a> ListenerAdapter<'a> {
+    pub(crate) fn new(
+        instance: &'a dyn AlkaidInstance,
+        time_limit: f64,
+        termination: Termination,
+        listener: &'a mut dyn SolveListener,
+    ) -> Self {
+        Self {
+            instance,
+            listener,
+            termination,
+            time_limit,
+            iterations: 0,
+            last_improvement: 0,
+            best_objective: None,
+            panic: None,
+        }
+    }
+
+    /// runs `callback` unless an earlier callback panicked, keeping its panic for
+    /// [`ListenerAdapter::resume_panic`].
+    fn guard<R>(&mut self, default: R, callback: impl FnOnce(&mut Self) -> R) -> R {
+        if self.panic.is_some() {
+            return default;
+        }
+        match catch_unwind(AssertUnwindSafe(|| callback(self))) {
+            Ok(result) => result,
+            Err(payload) => {
+                self.panic = Some(payload);
+                default
+            }
+        }
+    }
+
+    /// resumes the panic caught in a callback, if any.
+    pub(crate) fn resume_panic(&mut self) {
+        if let Some(payload) = self.panic.take() {
+            resume_unwind(payload);
+        }
+    }
+
+    fn is_terminated(&self) -> bool {
+        let Termination {
+            max_iterations,
+            target_objective,
+            max_stagnation,
+        } = self.termination;
+        max_iterations.is_some_and(|max| self.iterations >= max)
+            || target_objective
+                .zip(self.best_objective)
+                .is_some_and(|(target, best)| best <= target)
+            || max_stagnation.is_some_and(|max| self.iterations - self.last_improvement >= max)
+    }
+
+    /// decodes routes of the solver. the objective is the cost of the routes and the penalty of
+    /// the demand they leave unserved rather than the objective of the solver, which includes the
+    /// vehicle penalty and relies on the operators evaluating their moves correctly.
+    pub(crate) fn decode(&self, routes: Vec<i32>) -> Solution {
+        let mut solution = Solution::new(self.instance, split_results(routes), 0);
+        solution.objective = solution.penalized_cost(self.instance);
+        solution
+    }
+
+    pub(crate) fn on_start(&mut self) {
+        self.guard((), |adapter| adapter.listener.on_start());
+    }
+
+    pub(crate) fn on_updated(&mut self, routes: Vec<i32>, _objective: i32) {
+        self.guard((), |adapter| {
+            let solution = adapter.decode(routes);
+            adapter.best_objective = Some(solution.objective);
+            adapter.last_improvement = adapter.iterations;
+            adapter.listener.on_updated(&solution, solution.objective);
+        });
+    }
+
+    /// the progress of the search measured in iterations, or `-1.0` if it is measured in time.
+    ///
+    /// progress is only measured in iterations when there is no time limit, so that the
+    /// acceptance rule does not depend on the wall-clock time.
+    pub(crate) fn progress(&self) -> f64 {
+        match self.termination.max_iterations {
+            Some(max_iterations) if self.time_limit.is_infinite() => {
+                self.iterations as f64 / max_iterations as f64
+            }
+            _ => -1.0,
+        }
+    }
+
+    pub(crate) fn should_stop(&mut self) -> bool {
+        self.iterations += 1;
+        self.guard(true, |adapter| {
+            adapter.is_terminated() || adapter.listener.should_stop()
+        })
+    }
+}
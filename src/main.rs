@@ -1,107 +1,352 @@
-use sdvrp::{config, instance, solve_sdvrp};
-
-#[rustfmt::skip]
-fn dense_matrix_example() -> instance::Instance {
-    instance::Instance::from_dense_matrix(
-        100,
-        vec![60, 90, 60, 90, 60, 90, 60, 90],
-        vec![
-            vec![0, 100000, 100000, 100000, 100000, 200000, 200000, 200000, 200000],
-            vec![100000, 0, 141421, 200000, 141421, 100000, 223607, 300000, 223607],
-            vec![100000, 141421, 0, 141421, 200000, 223607, 100000, 223607, 300000],
-            vec![100000, 200000, 141421, 0, 141421, 300000, 223607, 100000, 223607],
-            vec![100000, 141421, 200000, 141421, 0, 223607, 300000, 223607, 100000],
-            vec![200000, 100000, 223607, 300000, 223607, 0, 282843, 400000, 282843],
-            vec![200000, 223607, 100000, 223607, 300000, 282843, 0, 282843, 400000],
-            vec![200000, 300000, 223607, 100000, 223607, 400000, 282843, 0, 282843],
-            vec![200000, 223607, 300000, 223607, 100000, 282843, 400000, 282843, 0],
-        ],
-    )
-}
-
-#[rustfmt::skip]
-fn coord_list_example() -> instance::Instance {
-    instance::Instance::from_coord_list(
-        100,
-        vec![
-            60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90,
-            60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90,
-            60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90, 60, 90,
-        ],
-        vec![
-            (    0,      0),
-            ( 1000,      0),
-            (  924,    383),
-            (  707,    707),
-            (  383,    924),
-            (    0,   1000),
-            ( -383,    924),
-            ( -707,    707),
-            ( -924,    383),
-            (-1000,      0),
-            ( -924,   -383),
-            ( -707,   -707),
-            ( -383,   -924),
-            (   -0,  -1000),
-            (  383,   -924),
-            (  707,   -707),
-            (  924,   -383),
-            ( 2000,      0),
-            ( 1848,    765),
-            ( 1414,   1414),
-            (  765,   1848),
-            (    0,   2000),
-            ( -765,   1848),
-            (-1414,   1414),
-            (-1848,    766),
-            (-2000,      0),
-            (-1848,   -765),
-            (-1414,  -1414),
-            ( -766,  -1848),
-            (   -0,  -2000),
-            (  765,  -1848),
-            ( 1414,  -1414),
-            ( 1848,   -766),
-            ( 3000,      0),
-            ( 2772,   1148),
-            ( 2121,   2121),
-            ( 1148,   2772),
-            (    0,   3000),
-            (-1148,   2772),
-            (-2121,   2121),
-            (-2772,   1148),
-            (-3000,      0),
-            (-2772,  -1148),
-            (-2122,  -2121),
-            (-1148,  -2771),
-            (   -0,  -3000),
-            ( 1148,  -2772),
-            ( 2121,  -2122),
-            ( 2771,  -1149),
-            ( 4000,      0),
-            ( 3696,   1531),
-            ( 2828,   2828),
-            ( 1531,   3695),
-            (    0,   4000),
-            (-1531,   3696),
-            (-2828,   2829),
-            (-3695,   1531),
-            (-4000,      0),
-            (-3696,  -1530),
-            (-2829,  -2828),
-            (-1531,  -3695),
-            (   -1,  -4000),
-            ( 1530,  -3696),
-            ( 2828,  -2829),
-            ( 3695,  -1531),
-        ],
-    )
-}
-
-fn main() {
-    let mut config = config::Config::default();
-    config.time_limit = 5.0;
-    println!("{:?}", solve_sdvrp(&config, &dense_matrix_example()));
-    config.time_limit = 10.0;
-    println!("{:?}", solve_sdvrp(&config, &coord_list_example()));
+use sdvrp::{
+    ParseError, PrintListener, SolveError,
+    config::{AcceptanceRuleType, Config, InterOperators, IntraOperators, RuinMethodType, Sorter},
+    instance::Instance,
+    solve_sdvrp_with_listener,
+};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: sdvrp [OPTIONS] <INSTANCE>
+
+Solves the split delivery vehicle routing problem of INSTANCE, a TSPLIB / CVRPLIB
+`.vrp` file or, if its extension is `.json`, a JSON instance.
+
+Options:
+  -c, --config <FILE>            read the solver configuration from a JSON file,
+                                 the options below override its values
+  -o, --output <FILE>            write the solution to FILE instead of stdout
+  -f, --format <FORMAT>          `sol` or `json`, defaults to the extension of
+                                 the output file or `sol`
+  -v, --verbose                  print the progress of the search to stderr
+      --max-vehicles <N>         limit the fleet of the instance to N vehicles
+      --random-seed <SEED>
+      --time-limit <SECONDS>
+      --blink-rate <RATE>
+      --inter-operators <LIST>   comma separated, e.g. `Relocate,Swap20,SdSwapStar`
+      --intra-operators <LIST>   comma separated, e.g. `Exchange,OrOpt1`
+      --acceptance <RULE>        `HC`, `HCWE`, `LAHC:<length>` or
+                                 `SA:<initial_temperature>:<decay>`
+      --ruin-method <METHOD>     `SISRs:<average_customers>:<max_length>:<split_rate>:<preserved_probability>`
+                                 or `Random:<size>,<size>,...`
+      --sorters <LIST>           comma separated `<sorter>:<weight>`, e.g. `Random:0.078,Far:0.942`
+      --max-iterations <N>
+      --target-objective <OBJECTIVE>
+      --max-stagnation <N>
+  -h, --help                     print this help
+
+The names of operators, acceptance rules, ruin methods and sorters ignore case.
+
+Exit codes: 0 on success, 1 if the solver fails, finds no solution the fleet can serve or the output
+cannot be written, 2 on invalid arguments, 3 on an invalid instance or configuration.";
+
+/// an error that ends the program with the given exit code.
+struct Failure {
+    code: u8,
+    message: String,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: 2,
+            message: message.into(),
+        }
+    }
+
+    fn input(message: impl Into<String>) -> Self {
+        Self {
+            code: 3,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<SolveError> for Failure {
+    fn from(e: SolveError) -> Self {
+        match e {
+            SolveError::InvalidConfig(_)
+            | SolveError::InvalidInstance(_)
+            | SolveError::InvalidInitialSolution(_) => Self::input(e.to_string()),
+            SolveError::FleetExceeded { .. }
+            | SolveError::FleetAssignment
+            | SolveError::Cancelled
+            | SolveError::Solver(_) => Self {
+                code: 1,
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Sol,
+    Json,
+}
+
+struct Args {
+    instance: PathBuf,
+    config: Option<PathBuf>,
+    output: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    verbose: bool,
+    max_vehicles: Option<usize>,
+    /// the configuration options in the order they are given.
+    overrides: Vec<(String, String)>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, Failure> {
+    let mut instance = None;
+    let mut config = None;
+    let mut output = None;
+    let mut format = None;
+    let mut verbose = false;
+    let mut max_vehicles = None;
+    let mut overrides = vec![];
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| Failure::usage(format!("{} requires a value", flag)))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-v" | "--verbose" => verbose = true,
+            "-c" | "--config" => config = Some(PathBuf::from(value()?)),
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => {
+                format = Some(match value()?.as_str() {
+                    "sol" => OutputFormat::Sol,
+                    "json" => OutputFormat::Json,
+                    other => return Err(Failure::usage(format!("unknown format `{}`", other))),
+                })
+            }
+            "--max-vehicles" => max_vehicles = Some(parse_number(&flag, &value()?)?),
+            "--random-seed" | "--time-limit" | "--blink-rate" | "--inter-operators"
+            | "--intra-operators" | "--acceptance" | "--ruin-method" | "--sorters"
+            | "--max-iterations" | "--target-objective" | "--max-stagnation" => {
+                let value = value()?;
+                overrides.push((flag, value));
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(Failure::usage(format!("unknown option `{}`", flag)));
+            }
+            _ if instance.is_none() => instance = Some(PathBuf::from(arg)),
+            _ => return Err(Failure::usage(format!("unexpected argument `{}`", arg))),
+        }
+    }
+    Ok(Some(Args {
+        instance: instance.ok_or_else(|| Failure::usage("missing INSTANCE"))?,
+        config,
+        output,
+        format,
+        verbose,
+        max_vehicles,
+        overrides,
+    }))
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, Failure> {
+    value
+        .parse()
+        .map_err(|_| Failure::usage(format!("invalid value `{}` for {}", value, flag)))
+}
+
+/// finds the variant of `all` whose name matches `name`, ignoring case.
+fn parse_variant<T: Copy + std::fmt::Debug>(
+    flag: &str,
+    all: &[T],
+    name: &str,
+) -> Result<T, Failure> {
+    all.iter()
+        .copied()
+        .find(|variant| format!("{:?}", variant).eq_ignore_ascii_case(name))
+        .ok_or_else(|| Failure::usage(format!("unknown value `{}` for {}", name, flag)))
+}
+
+/// splits `value` into its `:` separated parts, with the name in the first part in lowercase.
+fn split_parts(value: &str) -> (String, Vec<&str>) {
+    let mut parts = value.split(':');
+    let name = parts.next().unwrap_or_default().to_ascii_lowercase();
+    (name, parts.collect())
+}
+
+fn parse_acceptance(flag: &str, value: &str) -> Result<AcceptanceRuleType, Failure> {
+    let (name, parts) = split_parts(value);
+    match (name.as_str(), parts.as_slice()) {
+        ("hc", []) => Ok(AcceptanceRuleType::HC),
+        ("hcwe", []) => Ok(AcceptanceRuleType::HCWE),
+        ("lahc", [length]) => Ok(AcceptanceRuleType::LAHC(parse_number(flag, length)?)),
+        ("sa", [initial_temperature, decay]) => Ok(AcceptanceRuleType::SA(
+            parse_number(flag, initial_temperature)?,
+            parse_number(flag, decay)?,
+        )),
+        _ => Err(Failure::usage(format!(
+            "invalid value `{}` for {}",
+            value, flag
+        ))),
+    }
+}
+
+fn parse_ruin_method(flag: &str, value: &str) -> Result<RuinMethodType, Failure> {
+    let (name, parts) = split_parts(value);
+    match (name.as_str(), parts.as_slice()) {
+        (
+            "sisrs",
+            [
+                average_customers,
+                max_length,
+                split_rate,
+                preserved_probability,
+            ],
+        ) => Ok(RuinMethodType::SISRs(
+            parse_number(flag, average_customers)?,
+            parse_number(flag, max_length)?,
+            parse_number(flag, split_rate)?,
+            parse_number(flag, preserved_probability)?,
+        )),
+        ("random", [sizes]) => Ok(RuinMethodType::Random(
+            sizes
+                .split(',')
+                .map(|size| parse_number(flag, size))
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => Err(Failure::usage(format!(
+            "invalid value `{}` for {}",
+            value, flag
+        ))),
+    }
+}
+
+fn apply_override(config: &mut Config, flag: &str, value: &str) -> Result<(), Failure> {
+    match flag {
+        "--random-seed" => config.random_seed = parse_number(flag, value)?,
+        "--time-limit" => config.time_limit = parse_number(flag, value)?,
+        "--blink-rate" => config.blink_rate = parse_number(flag, value)?,
+        "--inter-operators" => {
+            config.inter_operators = value
+                .split(',')
+                .map(|name| parse_variant(flag, &InterOperators::ALL, name))
+                .collect::<Result<_, _>>()?
+        }
+        "--intra-operators" => {
+            config.intra_operators = value
+                .split(',')
+                .map(|name| parse_variant(flag, &IntraOperators::ALL, name))
+                .collect::<Result<_, _>>()?
+        }
+        "--acceptance" => config.acceptance_rule_type = parse_acceptance(flag, value)?,
+        "--ruin-method" => config.ruin_method_type = parse_ruin_method(flag, value)?,
+        "--sorters" => {
+            config.sorters = value
+                .split(',')
+                .map(|sorter| {
+                    let (name, weight) = sorter.split_once(':').ok_or_else(|| {
+                        Failure::usage(format!("invalid value `{}` for {}", sorter, flag))
+                    })?;
+                    Ok((
+                        parse_variant(flag, &Sorter::ALL, name)?,
+                        parse_number(flag, weight)?,
+                    ))
+                })
+                .collect::<Result<_, Failure>>()?
+        }
+        "--max-iterations" => config.termination.max_iterations = Some(parse_number(flag, value)?),
+        "--target-objective" => {
+            config.termination.target_objective = Some(parse_number(flag, value)?)
+        }
+        "--max-stagnation" => config.termination.max_stagnation = Some(parse_number(flag, value)?),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+fn read_instance(path: &Path) -> Result<Instance, Failure> {
+    let input_error = |e: ParseError| Failure::input(format!("{}: {}", path.display(), e));
+    if has_extension(path, "json") {
+        let s = std::fs::read_to_string(path).map_err(|e| input_error(e.into()))?;
+        serde_json::from_str(&s).map_err(|e| Failure::input(format!("{}: {}", path.display(), e)))
+    } else {
+        Instance::from_vrp_file(path).map_err(input_error)
+    }
+}
+
+fn read_config(path: &Path) -> Result<Config, Failure> {
+    let s = std::fs::read_to_string(path)
+        .map_err(|e| Failure::input(format!("{}: {}", path.display(), e)))?;
+    serde_json::from_str(&s).map_err(|e| Failure::input(format!("{}: {}", path.display(), e)))
+}
+
+fn run(args: Args) -> Result<(), Failure> {
+    let mut instance = read_instance(&args.instance)?;
+    if let Some(max_vehicles) = args.max_vehicles {
+        instance = instance
+            .with_max_vehicles(max_vehicles)
+            .map_err(|e| Failure::input(e.to_string()))?;
+    }
+    let mut config = match &args.config {
+        Some(path) => read_config(path)?,
+        None => Config::default(),
+    };
+    for (flag, value) in &args.overrides {
+        apply_override(&mut config, flag, value)?;
+    }
+    let solution = if args.verbose {
+        solve_sdvrp_with_listener(&config, &instance, &mut PrintListener::stderr())?
+    } else {
+        solve_sdvrp_with_listener(&config, &instance, &mut ())?
+    };
+    let format = args.format.unwrap_or(match &args.output {
+        Some(path) if has_extension(path, "json") => OutputFormat::Json,
+        _ => OutputFormat::Sol,
+    });
+    let output = match format {
+        OutputFormat::Sol => solution.to_sol_string(&instance),
+        OutputFormat::Json => serde_json::to_string_pretty(&solution).unwrap() + "\n",
+    };
+    match &args.output {
+        Some(path) => std::fs::write(path, output).map_err(|e| Failure {
+            code: 1,
+            message: format!("{}: {}", path.display(), e),
+        }),
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => run(args),
+        Ok(None) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e.message);
+            if e.code == 2 {
+                eprintln!("run `sdvrp --help` for usage");
+            }
+            ExitCode::from(e.code)
+        }
+    }
 }
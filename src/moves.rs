@@ -0,0 +1,207 @@
+use crate::instance::{AlkaidInstance, min_delivery, route_distance, route_peak_load, visit_limit};
+use crate::limits::within_limits;
+
+/// routes of `(customer, load)` visits, each served by a vehicle of its own capacity, between which
+/// load is moved to repair the routes of the search. every move keeps every route within its
+/// capacity, the route limits and the split policy of the instance.
+pub(crate) struct Routes<'a, T: ?Sized> {
+    instance: &'a T,
+    pub(crate) visits: Vec<Vec<(i32, i32)>>,
+    pub(crate) capacities: Vec<i32>,
+}
+
+impl<'a, T: AlkaidInstance + ?Sized> Routes<'a, T> {
+    pub(crate) fn new(instance: &'a T, visits: Vec<Vec<(i32, i32)>>, capacities: Vec<i32>) -> Self {
+        Self {
+            instance,
+            visits,
+            capacities,
+        }
+    }
+
+    /// whether `route` may be served as `visits`.
+    fn fits(&self, route: usize, visits: &[(i32, i32)]) -> bool {
+        route_peak_load(self.instance, visits) <= self.capacities[route]
+            && within_limits(self.instance, &customers(visits))
+    }
+
+    /// how much more `route` may deliver without exceeding its capacity. adding a delivery to the
+    /// route raises its peak load by at most the delivery.
+    pub(crate) fn room(&self, route: usize) -> i32 {
+        (self.capacities[route] - route_peak_load(self.instance, &self.visits[route])).max(0)
+    }
+
+    /// how many more visits `customer` may receive, or `None` if it may receive any number.
+    fn visits_left(&self, customer: usize) -> Option<usize> {
+        let visits = self
+            .visits
+            .iter()
+            .flatten()
+            .filter(|&&(c, _)| c as usize == customer)
+            .count();
+        visit_limit(self.instance, customer).map(|max_visits| max_visits.saturating_sub(visits))
+    }
+
+    /// whether `customer` may receive one more visit.
+    pub(crate) fn may_visit(&self, customer: usize) -> bool {
+        self.visits_left(customer) != Some(0)
+    }
+
+    /// the distance `route` gains from a visit of `customer` at `position`.
+    fn insertion_cost(&self, route: usize, position: usize, customer: usize) -> i32 {
+        let mut inserted = customers(&self.visits[route]);
+        let before = route_distance(self.instance, &inserted);
+        inserted.insert(position, customer);
+        route_distance(self.instance, &inserted) - before
+    }
+
+    /// the route and position where a visit delivering `load` to `customer` adds the least
+    /// distance, of the routes accepted by `allowed`.
+    pub(crate) fn cheapest_insertion(
+        &self,
+        customer: usize,
+        load: i32,
+        allowed: impl Fn(usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        let mut best = None;
+        for route in (0..self.visits.len()).filter(|&route| allowed(route)) {
+            for position in 0..=self.visits[route].len() {
+                let mut inserted = self.visits[route].clone();
+                inserted.insert(position, (customer as i32, load));
+                if !self.fits(route, &inserted) {
+                    continue;
+                }
+                let cost = self.insertion_cost(route, position, customer);
+                if best.is_none_or(|(_, _, best)| cost < best) {
+                    best = Some((route, position, cost));
+                }
+            }
+        }
+        best.map(|(route, position, _)| (route, position))
+    }
+
+    /// adds up to `quantity` to the existing visits of `customer`, returning what is left.
+    fn top_up(&mut self, customer: usize, mut quantity: i32) -> i32 {
+        let min_load = min_delivery(self.instance, customer);
+        for route in 0..self.visits.len() {
+            let Some(i) = self.visits[route]
+                .iter()
+                .position(|&(c, _)| c as usize == customer)
+            else {
+                continue;
+            };
+            let amount = allowed_part(quantity, self.room(route), min_load);
+            if amount > 0 {
+                let mut visits = self.visits[route].clone();
+                visits[i].1 += amount;
+                if self.fits(route, &visits) {
+                    self.visits[route] = visits;
+                    quantity -= amount;
+                }
+            }
+            if quantity == 0 {
+                break;
+            }
+        }
+        quantity
+    }
+
+    /// delivers `quantity` to `customer`: first by the existing visits of the customer, then by
+    /// the cheapest insertion of a visit of the whole quantity or, as long as the customer may
+    /// receive more visits, of as much as a route has room for. returns the quantity that could
+    /// not be delivered.
+    pub(crate) fn deliver(&mut self, customer: usize, quantity: i32) -> i32 {
+        let mut quantity = self.top_up(customer, quantity);
+        let min_load = min_delivery(self.instance, customer);
+        while quantity > 0 && self.may_visit(customer) {
+            if let Some((route, position)) = self.cheapest_insertion(customer, quantity, |_| true) {
+                self.visits[route].insert(position, (customer as i32, quantity));
+                return 0;
+            }
+            // a part needs another visit for the rest.
+            if self.visits_left(customer).is_some_and(|left| left < 2) {
+                break;
+            }
+            let best = (0..self.visits.len())
+                .filter_map(|route| {
+                    let part = allowed_part(quantity, self.room(route), min_load);
+                    if part < min_load {
+                        return None;
+                    }
+                    let (route, position) =
+                        self.cheapest_insertion(customer, part, |r| r == route)?;
+                    Some((route, position, part))
+                })
+                .min_by_key(|&(route, position, part)| {
+                    // the cheapest per unit delivered.
+                    self.insertion_cost(route, position, customer) as i64 * quantity as i64
+                        / part as i64
+                });
+            let Some((route, position, part)) = best else {
+                break;
+            };
+            self.visits[route].insert(position, (customer as i32, part));
+            quantity -= part;
+        }
+        quantity
+    }
+
+    /// moves load out of `route` into the other routes until it has room for `needed` more, taking
+    /// from its last visits first. leaves the routes unchanged and returns `false` if the other
+    /// routes cannot take enough.
+    pub(crate) fn make_room(&mut self, route: usize, needed: i32) -> bool {
+        let visits = self.visits.clone();
+        let capacity = self.capacities[route];
+        let mut index = self.visits[route].len();
+        while self.room(route) < needed && index > 0 {
+            index -= 1;
+            let (customer, load) = self.take(route, index, needed - self.room(route));
+            // no room in `route` while the load moves elsewhere.
+            self.capacities[route] = 0;
+            let rest = self.deliver(customer as usize, load);
+            self.capacities[route] = capacity;
+            if rest > 0 {
+                break;
+            }
+        }
+        if self.room(route) < needed {
+            self.visits = visits;
+            return false;
+        }
+        true
+    }
+
+    /// removes `amount`, or at least the minimum load of the customer, from the visit at `index` of `route`, or the whole visit if the
+    /// rest would be below the minimum load of the customer or the customer may not receive
+    /// another visit for the removed load. returns the customer and the removed load.
+    pub(crate) fn take(&mut self, route: usize, index: usize, amount: i32) -> (i32, i32) {
+        let (customer, load) = self.visits[route][index];
+        let min_load = min_delivery(self.instance, customer as usize);
+        let amount = amount.max(min_load);
+        let rest = load - amount;
+        if rest > 0 && rest >= min_load && self.may_visit(customer as usize) {
+            self.visits[route][index].1 = rest;
+            (customer, amount)
+        } else {
+            self.visits[route].remove(index);
+            (customer, load)
+        }
+    }
+}
+
+/// the largest part of `quantity` up to `room` that leaves no remainder below `min_load`.
+fn allowed_part(quantity: i32, room: i32, min_load: i32) -> i32 {
+    let part = room.min(quantity);
+    if quantity - part > 0 && quantity - part < min_load {
+        (quantity - min_load).max(0)
+    } else {
+        part.max(0)
+    }
+}
+
+pub(crate) fn customers(visits: &[(i32, i32)]) -> Vec<usize> {
+    visits
+        .iter()
+        .map(|&(customer, _)| customer as usize)
+        .collect()
+}
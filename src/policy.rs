@@ -0,0 +1,128 @@
+use crate::instance::{AlkaidInstance, max_split, min_delivery, min_split, visit_limit};
+use crate::moves::Routes;
+use crate::solution::Solution;
+
+/// delivers the demand of every customer that is visited more often than its maximum number of
+/// visits, or than once if it has a pickup, or by a visit below its minimum load, again: by the
+/// cheapest insertions into the routes with room left, then by moving load out of a route to make
+/// room for the rest, or else by as few new routes of its own as its demand needs.
+///
+/// the search splits every demand freely, so its routes may break the split policy of the
+/// instance. [`instance::validate`] ensures that the demand of every customer can be split into
+/// allowed visits, and that every customer fits a route of its own, so the repair always succeeds.
+///
+/// [`instance::validate`]: crate::instance::validate
+pub(crate) fn repair_split_policy<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    solution: Solution,
+    capacity: i32,
+) -> Solution {
+    let mut loads = vec![vec![]; instance.demands().len()];
+    for route in &solution.routes {
+        for &(customer, load) in &route.visits {
+            loads[customer as usize - 1].push(load);
+        }
+    }
+    let broken = loads
+        .iter()
+        .enumerate()
+        .filter(|&(i, loads)| {
+            let customer = i + 1;
+            visit_limit(instance, customer).is_some_and(|max_visits| loads.len() > max_visits)
+                || loads
+                    .iter()
+                    .any(|&load| load < min_delivery(instance, customer))
+        })
+        .map(|(i, _)| i + 1)
+        .collect::<Vec<_>>();
+    if broken.is_empty() {
+        return solution;
+    }
+    let mut routes = solution.to_routes();
+    for visits in &mut routes {
+        visits.retain(|&(customer, _)| !broken.contains(&(customer as usize)));
+    }
+    let capacities = vec![capacity; routes.len()];
+    let mut routes = Routes::new(instance, routes, capacities);
+    for customer in broken {
+        let demand = instance.demands()[customer - 1];
+        let mut rest = routes.deliver(customer, demand);
+        // another route would need another vehicle, so the load of the routes moves first.
+        for route in 0..routes.visits.len() {
+            if rest == 0 || !routes.may_visit(customer) {
+                break;
+            }
+            if routes.make_room(route, rest)
+                && let Some((route, position)) =
+                    routes.cheapest_insertion(customer, rest, |r| r == route)
+            {
+                routes.visits[route].insert(position, (customer as i32, rest));
+                rest = 0;
+            }
+        }
+        if rest > 0 {
+            // at least the capacity allows, at most the split policy allows.
+            for visits in &mut routes.visits {
+                visits.retain(|&(c, _)| c as usize != customer);
+            }
+            let parts =
+                min_split(instance, capacity, customer).min(max_split(instance, customer)) as i32;
+            for part in 0..parts {
+                let load = demand / parts + i32::from(part < demand % parts);
+                routes.visits.push(vec![(customer as i32, load)]);
+                routes.capacities.push(capacity);
+            }
+        }
+    }
+    let mut solution = Solution::new(instance, routes.visits, 0);
+    solution.objective = solution.cost();
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::Instance;
+
+    fn instance() -> Instance {
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+    }
+
+    #[test]
+    fn test_repair() {
+        let instance = instance()
+            .with_max_visits(vec![2, 1, 2])
+            .unwrap()
+            .with_min_delivery(5)
+            .unwrap();
+        let solution = Solution::new(
+            &instance,
+            vec![vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]],
+            0,
+        );
+        let solution = repair_split_policy(&instance, solution, 10);
+        assert!(solution.violations(&instance).is_empty());
+        assert_eq!(solution.objective, solution.cost());
+    }
+
+    #[test]
+    fn test_full_routes() {
+        // both routes are full, so load of customer 1 moves out of the first route to make room
+        // for the whole demand of customer 3.
+        let instance =
+            Instance::from_coord_list(10, vec![6, 6, 8], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+                .with_max_visits(vec![2, 2, 1])
+                .unwrap();
+        let solution = Solution::new(
+            &instance,
+            vec![vec![(1, 6), (3, 4)], vec![(2, 6), (3, 4)]],
+            0,
+        );
+        let solution = repair_split_policy(&instance, solution, 10);
+        assert_eq!(
+            solution.to_routes(),
+            vec![vec![(3, 8), (1, 2)], vec![(1, 4), (2, 6)]]
+        );
+        assert!(solution.violations(&instance).is_empty());
+    }
+}
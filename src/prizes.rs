@@ -0,0 +1,176 @@
+use crate::instance::{AlkaidInstance, route_distance};
+use crate::limits::within_limits;
+use crate::moves::{Routes, customers};
+use crate::solution::{Route, Solution};
+
+/// the penalty for leaving the `load` of `customer` undelivered.
+fn penalty<T: AlkaidInstance + ?Sized>(instance: &T, customer: i32, load: i32) -> i64 {
+    let unserved_penalties = instance.unserved_penalties().unwrap_or_default();
+    unserved_penalties[customer as usize - 1] as i64 * load as i64
+}
+
+/// removes visits whose detour is longer than the penalty for leaving their load undelivered,
+/// the most profitable first, until no removal pays off.
+///
+/// the search must deliver every demand, so it cannot weigh a delivery against its penalty. a
+/// visit is only removed if its route still respects the route limits without it, and visits of
+/// customers with a pickup are kept, since the pickup has no penalty to weigh its detour against.
+pub(crate) fn drop_unprofitable_visits<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    solution: Solution,
+) -> Solution {
+    if instance.unserved_penalties().is_none() {
+        return solution;
+    }
+    let pickups = instance.pickups().unwrap_or_default();
+    let has_pickup = |customer: i32| pickups.get(customer as usize - 1).is_some_and(|&p| p > 0);
+    let mut routes = solution.to_routes();
+    let mut changed = false;
+    loop {
+        let mut best = None;
+        for (route, visits) in routes.iter().enumerate() {
+            let customers = visits
+                .iter()
+                .map(|&(customer, _)| customer as usize)
+                .collect::<Vec<_>>();
+            let distance = route_distance(instance, &customers);
+            for (position, &(customer, load)) in visits.iter().enumerate() {
+                if has_pickup(customer) {
+                    continue;
+                }
+                let mut removed = customers.clone();
+                removed.remove(position);
+                if !within_limits(instance, &removed) {
+                    continue;
+                }
+                let saving = (distance - route_distance(instance, &removed)) as i64
+                    - penalty(instance, customer, load);
+                if saving > 0 && best.is_none_or(|(_, _, best)| saving > best) {
+                    best = Some((route, position, saving));
+                }
+            }
+        }
+        let Some((route, position, _)) = best else {
+            break;
+        };
+        routes[route].remove(position);
+        changed = true;
+    }
+    if !changed {
+        return solution;
+    }
+    routes.retain(|visits| !visits.is_empty());
+    let mut solution = Solution::new(instance, routes, 0);
+    solution.objective = solution.penalized_cost(instance);
+    solution
+}
+
+/// removes the routes whose loads have the smallest penalty compared to their distance until
+/// there are at most `max_vehicles` routes, then delivers what the remaining routes have room for,
+/// see [`fill_unserved`].
+pub(crate) fn drop_routes<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    mut solution: Solution,
+    max_vehicles: usize,
+) -> Solution {
+    let loss = |visits: &[(i32, i32)], distance: i32| {
+        visits
+            .iter()
+            .map(|&(customer, load)| penalty(instance, customer, load))
+            .sum::<i64>()
+            - distance as i64
+    };
+    let mut order = (0..solution.routes.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| loss(&solution.routes[i].visits, solution.routes[i].distance));
+    let dropped = &order[..solution.routes.len().saturating_sub(max_vehicles)];
+    let mut i = 0;
+    solution.routes.retain(|_| {
+        i += 1;
+        !dropped.contains(&(i - 1))
+    });
+    fill_unserved(instance, solution)
+}
+
+/// delivers the [`unserved`](Solution::unserved) demand, the customers with the highest penalty
+/// first, as far as the routes have room for it and the added distance costs less than the
+/// penalty of the delivered load. the demand of a customer may be served in part.
+///
+/// the search delivers whole demands, so the routes that are kept when others are dropped may
+/// have room left for a part of the dropped loads.
+fn fill_unserved<T: AlkaidInstance + ?Sized>(instance: &T, mut solution: Solution) -> Solution {
+    let capacity = |route: &Route| {
+        route
+            .vehicle_type
+            .zip(instance.fleet())
+            .map_or(instance.capacity(), |(i, fleet)| fleet[i].capacity)
+    };
+    let capacities = solution.routes.iter().map(capacity).collect();
+    let mut routes = Routes::new(instance, solution.to_routes(), capacities);
+    let distance = |visits: &[Vec<(i32, i32)>]| {
+        visits
+            .iter()
+            .map(|visits| route_distance(instance, &customers(visits)) as i64)
+            .sum::<i64>()
+    };
+    let mut unserved = solution.unserved(instance);
+    unserved.sort_by_key(|&(customer, _)| std::cmp::Reverse(penalty(instance, customer, 1)));
+    for (customer, quantity) in unserved {
+        let visits = routes.visits.clone();
+        let rest = routes.deliver(customer as usize, quantity);
+        if distance(&routes.visits) - distance(&visits)
+            >= penalty(instance, customer, quantity - rest)
+        {
+            routes.visits = visits;
+        }
+    }
+    for (route, visits) in solution.routes.iter_mut().zip(routes.visits) {
+        *route = Route {
+            vehicle_type: route.vehicle_type,
+            depot: route.depot,
+            ..Route::new(instance, visits)
+        };
+    }
+    solution.objective = solution.penalized_cost(instance);
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::Instance;
+
+    #[test]
+    fn test_drop_unprofitable_visits() {
+        // customer 2 is 100 away there and back, but leaving it unserved costs 5.
+        let instance = Instance::from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0), (50, 0)])
+            .with_unserved_penalties(vec![100, 1])
+            .unwrap();
+        let solution = Solution::new(&instance, vec![vec![(1, 5)], vec![(2, 5)]], 0);
+        let solution = drop_unprofitable_visits(&instance, solution);
+        assert_eq!(solution.to_routes(), vec![vec![(1, 5)]]);
+        assert_eq!(solution.objective, 2 + 5);
+
+        // customer 2 has a pickup, which would be left behind with its delivery.
+        let instance = instance.with_pickups(vec![0, 3]).unwrap();
+        let solution = Solution::new(&instance, vec![vec![(1, 5)], vec![(2, 5)]], 0);
+        let solution = drop_unprofitable_visits(&instance, solution);
+        assert_eq!(solution.to_routes(), vec![vec![(1, 5)], vec![(2, 5)]]);
+        assert!(solution.violations(&instance).is_empty());
+    }
+
+    #[test]
+    fn test_drop_routes() {
+        // the fleet cannot carry both customers, so the one with the smaller penalty is served in
+        // part: {1: 8, 2: 2} costs 3 and leaves 6 unserved at 2 each.
+        let instance = Instance::from_coord_list(10, vec![8, 8], vec![(0, 0), (1, 0), (0, 1)])
+            .with_unserved_penalties(vec![3, 2])
+            .unwrap()
+            .with_max_vehicles(1)
+            .unwrap();
+        let solution = Solution::new(&instance, vec![vec![(1, 8)], vec![(2, 8)]], 0);
+        let solution = drop_routes(&instance, solution, 1);
+        assert!(solution.violations(&instance).is_empty());
+        assert_eq!(solution.unserved(&instance), vec![(2, 6)]);
+        assert_eq!(solution.objective, 3 + 6 * 2);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::error::ParseError;
+use crate::instance::AlkaidInstance;
+use crate::solution::{Route, Solution};
+use crate::vrplib::syntax_error;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+impl Solution {
+    /// formats the solution of `instance` in the CVRPLIB `.sol` format.
+    ///
+    /// every route is written as `Route #k: c1 c2 ...` followed by a final `Cost N` line with the
+    /// objective. a visit is written as `c(load)` if it does not deliver the whole demand of the
+    /// customer or the customer is visited more than once, otherwise as `c`.
+    ///
+    /// the vehicle type and depot of a route are written as `Route #k [vehicle type t, depot d]:`,
+    /// and the [`unserved`](Solution::unserved) demand as an `Unserved c(quantity) ...` line
+    /// before the cost. plain CVRPLIB solutions have neither.
+    pub fn to_sol_string<T: AlkaidInstance + ?Sized>(&self, instance: &T) -> String {
+        let mut visits = BTreeMap::new();
+        for &(customer, _) in self.routes.iter().flat_map(|route| &route.visits) {
+            *visits.entry(customer).or_insert(0) += 1;
+        }
+        let demand = |customer: i32| {
+            (customer as usize)
+                .checked_sub(1)
+                .and_then(|i| instance.demands().get(i).copied())
+        };
+        let mut s = String::new();
+        for (k, route) in self.routes.iter().enumerate() {
+            write!(s, "Route #{}", k + 1).unwrap();
+            let attributes = route
+                .vehicle_type
+                .map(|vehicle_type| format!("vehicle type {}", vehicle_type))
+                .into_iter()
+                .chain(route.depot.map(|depot| format!("depot {}", depot)))
+                .collect::<Vec<_>>();
+            if !attributes.is_empty() {
+                write!(s, " [{}]", attributes.join(", ")).unwrap();
+            }
+            s.push(':');
+            for &(customer, load) in &route.visits {
+                if visits[&customer] > 1 || demand(customer) != Some(load) {
+                    write!(s, " {}({})", customer, load).unwrap();
+                } else {
+                    write!(s, " {}", customer).unwrap();
+                }
+            }
+            s.push('\n');
+        }
+        let unserved = self.unserved(instance);
+        if !unserved.is_empty() {
+            s.push_str("Unserved");
+            for (customer, quantity) in unserved {
+                write!(s, " {}({})", customer, quantity).unwrap();
+            }
+            s.push('\n');
+        }
+        writeln!(s, "Cost {}", self.objective).unwrap();
+        s
+    }
+
+    /// writes the solution of `instance` to a file in the CVRPLIB `.sol` format, see
+    /// [`Solution::to_sol_string`].
+    pub fn write_sol_file<T: AlkaidInstance + ?Sized, P: AsRef<Path>>(
+        &self,
+        instance: &T,
+        path: P,
+    ) -> std::io::Result<()> {
+        std::fs::write(path, self.to_sol_string(instance))
+    }
+
+    /// reads a solution of `instance` in the CVRPLIB `.sol` format, as written by
+    /// [`Solution::to_sol_string`].
+    ///
+    /// a customer without an explicit `(load)` receives its whole demand. the objective is taken
+    /// from the `Cost` line, or computed from the routes if there is none. the `Unserved` line is
+    /// skipped, the unserved demand follows from the routes. the routes are not checked for
+    /// feasibility, see [`Solution::violations`].
+    pub fn from_sol_str<T: AlkaidInstance + ?Sized>(
+        instance: &T,
+        s: &str,
+    ) -> Result<Self, ParseError> {
+        let mut routes = vec![];
+        let mut objective = None;
+        for (i, text) in s.lines().enumerate() {
+            let line = i + 1;
+            let text = text.trim();
+            if let Some(rest) = text.strip_prefix("Route") {
+                let (header, visits) = rest
+                    .split_once(':')
+                    .ok_or_else(|| syntax_error(line, "expected `Route #k: ...`"))?;
+                let visits = visits
+                    .split_whitespace()
+                    .map(|token| parse_visit(instance, line, token))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !visits.is_empty() {
+                    let mut route = Route::new(instance, visits);
+                    parse_attributes(line, header, &mut route)?;
+                    routes.push(route);
+                }
+            } else if let Some(cost) = text.strip_prefix("Cost") {
+                let cost = cost.trim();
+                objective = Some(
+                    cost.parse::<i32>()
+                        .map_err(|_| syntax_error(line, format!("invalid cost `{}`", cost)))?,
+                );
+            }
+        }
+        let mut solution = Solution {
+            routes,
+            objective: 0,
+        };
+        solution.objective = objective.unwrap_or_else(|| solution.cost());
+        Ok(solution)
+    }
+
+    /// reads a solution of `instance` from a file in the CVRPLIB `.sol` format,
+    /// see [`Solution::from_sol_str`].
+    pub fn from_sol_file<T: AlkaidInstance + ?Sized, P: AsRef<Path>>(
+        instance: &T,
+        path: P,
+    ) -> Result<Self, ParseError> {
+        Self::from_sol_str(instance, &std::fs::read_to_string(path)?)
+    }
+}
+
+/// parses the `[vehicle type t, depot d]` attributes in the header of a route, if any.
+fn parse_attributes(line: usize, header: &str, route: &mut Route) -> Result<(), ParseError> {
+    let Some((_, attributes)) = header.split_once('[') else {
+        return Ok(());
+    };
+    let attributes = attributes
+        .trim()
+        .strip_suffix(']')
+        .ok_or_else(|| syntax_error(line, "expected `]` after the route attributes"))?;
+    for attribute in attributes.split(',') {
+        let attribute = attribute.trim();
+        let (target, index) = if let Some(index) = attribute.strip_prefix("vehicle type") {
+            (&mut route.vehicle_type, index)
+        } else if let Some(index) = attribute.strip_prefix("depot") {
+            (&mut route.depot, index)
+        } else {
+            return Err(syntax_error(
+                line,
+                format!("invalid route attribute `{}`", attribute),
+            ));
+        };
+        *target =
+            Some(index.trim().parse::<usize>().map_err(|_| {
+                syntax_error(line, format!("invalid route attribute `{}`", attribute))
+            })?);
+    }
+    Ok(())
+}
+
+/// parses a visit written as `customer` or `customer(load)`.
+fn parse_visit<T: AlkaidInstance + ?Sized>(
+    instance: &T,
+    line: usize,
+    token: &str,
+) -> Result<(i32, i32), ParseError> {
+    let (customer, load) = match token.strip_suffix(')') {
+        Some(visit) => {
+            let (customer, load) = visit
+                .split_once('(')
+                .ok_or_else(|| syntax_error(line, format!("invalid visit `{}`", token)))?;
+            (customer, Some(load))
+        }
+        None => (token, None),
+    };
+    let customer = customer
+        .parse::<i32>()
+        .ok()
+        .filter(|&c| c >= 1 && c as usize <= instance.demands().len())
+        .ok_or_else(|| syntax_error(line, format!("invalid customer `{}`", customer)))?;
+    let load = match load {
+        Some(load) => load
+            .parse::<i32>()
+            .map_err(|_| syntax_error(line, format!("invalid load `{}`", load)))?,
+        None => instance.demands()[customer as usize - 1],
+    };
+    Ok((customer, load))
+}
@@ -0,0 +1,425 @@
+use crate::feasibility::{Violation, check_feasibility};
+use crate::instance::{AlkaidInstance, route_distance, route_peak_load, route_schedule, saturate};
+use std::collections::BTreeMap;
+
+/// a route that starts and ends at the depot.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Route {
+    /// the visited customers in order, each with the load delivered to it.
+    pub visits: Vec<(i32, i32)>,
+    /// the travelled distance, see [`route_distance`].
+    pub distance: i32,
+    /// the total load delivered by the route.
+    pub load: i32,
+    /// the largest load on board along the route, see [`route_peak_load`].
+    pub peak_load: i32,
+    /// the time from leaving to returning to the depot, see
+    /// [`route_duration`](AlkaidInstance::route_duration).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub duration: i32,
+    /// the arrival time at every visit, see [`route_schedule`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub arrival_times: Vec<i32>,
+    /// the index of the vehicle type serving the route into the
+    /// [`fleet`](AlkaidInstance::fleet) of the instance, if it has one.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub vehicle_type: Option<usize>,
+    /// the index of the depot the route starts and ends at into the
+    /// [`depots`](crate::depot::MultiDepotInstance::depots) of a multi-depot instance.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub depot: Option<usize>,
+}
+
+impl Route {
+    /// creates a route from its visits, computing its distance, load and schedule on `instance`.
+    ///
+    /// a route that visits a node out of range, see [`Violation::NodeOutOfRange`], has no
+    /// distance, duration or arrival times, as they cannot be computed.
+    pub fn new<T: AlkaidInstance + ?Sized>(instance: &T, visits: Vec<(i32, i32)>) -> Self {
+        let customers = visits
+            .iter()
+            .map(|&(customer, _)| customer as usize)
+            .collect::<Vec<_>>();
+        let load = saturate(visits.iter().map(|&(_, load)| load as i64).sum());
+        let peak_load = route_peak_load(instance, &visits);
+        if customers
+            .iter()
+            .any(|&customer| customer > instance.demands().len())
+        {
+            return Self {
+                distance: 0,
+                peak_load,
+                duration: 0,
+                arrival_times: vec![],
+                visits,
+                load,
+                vehicle_type: None,
+                depot: None,
+            };
+        }
+        let schedule = route_schedule(instance, &customers);
+        Self {
+            distance: route_distance(instance, &customers),
+            peak_load,
+            duration: instance.route_duration(&customers),
+            arrival_times: schedule.arrivals,
+            visits,
+            load,
+            vehicle_type: None,
+            depot: None,
+        }
+    }
+}
+
+/// a solution returned by the solver.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution {
+    /// the routes of the solution, one per vehicle.
+    pub routes: Vec<Route>,
+    /// the objective value, computed from the routes rather than taken from the solver, see
+    /// [`solve_sdvrp_with_listener`](crate::solve_sdvrp_with_listener).
+    pub objective: i32,
+}
+
+impl Solution {
+    /// creates a solution from raw routes of `(customer, load)` pairs, see [`Route::new`]. routes
+    /// that do not fit `instance` are reported by [`violations`](Solution::violations).
+    pub fn new<T: AlkaidInstance + ?Sized>(
+        instance: &T,
+        routes: Vec<Vec<(i32, i32)>>,
+        objective: i32,
+    ) -> Self {
+        Self {
+            routes: routes
+                .into_iter()
+                .filter(|visits| !visits.is_empty())
+                .map(|visits| Route::new(instance, visits))
+                .collect(),
+            objective,
+        }
+    }
+
+    /// the number of vehicles used, i.e. the number of routes.
+    pub fn num_vehicles(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// the total distance travelled by all routes, saturated at `i32::MAX`.
+    pub fn cost(&self) -> i32 {
+        saturate(self.routes.iter().map(|route| route.distance as i64).sum())
+    }
+
+    /// the total cost of the routes on `instance`: the [`route_cost`](crate::fleet::VehicleType::route_cost) of
+    /// the vehicle type of every route, or its distance if it has no vehicle type.
+    pub fn fleet_cost<T: AlkaidInstance + ?Sized>(&self, instance: &T) -> i64 {
+        self.routes
+            .iter()
+            .map(|route| match (route.vehicle_type, instance.fleet()) {
+                (Some(i), Some(fleet)) => fleet[i].route_cost(route.distance),
+                _ => route.distance as i64,
+            })
+            .sum()
+    }
+
+    /// the customers that receive less than their demand, with the undelivered quantity.
+    pub fn unserved<T: AlkaidInstance + ?Sized>(&self, instance: &T) -> Vec<(i32, i32)> {
+        let mut unserved = instance.demands().to_vec();
+        for route in &self.routes {
+            for &(customer, load) in &route.visits {
+                if let Some(quantity) = (customer as usize)
+                    .checked_sub(1)
+                    .and_then(|i| unserved.get_mut(i))
+                {
+                    *quantity -= load;
+                }
+            }
+        }
+        unserved
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, quantity)| quantity > 0)
+            .map(|(i, quantity)| (i as i32 + 1, quantity))
+            .collect()
+    }
+
+    /// the total penalty for the [`unserved`](Solution::unserved) demand on `instance`, see
+    /// [`unserved_penalties`](AlkaidInstance::unserved_penalties).
+    pub fn unserved_cost<T: AlkaidInstance + ?Sized>(&self, instance: &T) -> i64 {
+        let unserved_penalties = instance.unserved_penalties().unwrap_or_default();
+        self.unserved(instance)
+            .iter()
+            .map(|&(customer, quantity)| {
+                unserved_penalties
+                    .get(customer as usize - 1)
+                    .map_or(0, |&penalty| penalty as i64 * quantity as i64)
+            })
+            .sum()
+    }
+
+    /// the objective of the solution on `instance`: its [`fleet_cost`](Solution::fleet_cost), which
+    /// is its [`cost`](Solution::cost) without vehicle types, plus its
+    /// [`unserved_cost`](Solution::unserved_cost), saturated at `i32::MAX`.
+    pub(crate) fn penalized_cost<T: AlkaidInstance + ?Sized>(&self, instance: &T) -> i32 {
+        saturate(self.fleet_cost(instance) + self.unserved_cost(instance))
+    }
+
+    /// the customers that are served by more than one route, with the number of routes serving them.
+    pub fn split_customers(&self) -> BTreeMap<i32, usize> {
+        let mut visits = BTreeMap::new();
+        for route in &self.routes {
+            let mut customers = route.visits.iter().map(|&(c, _)| c).collect::<Vec<_>>();
+            customers.sort_unstable();
+            customers.dedup();
+            for customer in customers {
+                *visits.entry(customer).or_insert(0) += 1;
+            }
+        }
+        visits.retain(|_, count| *count > 1);
+        visits
+    }
+
+    /// the routes as raw `(customer, load)` pairs, as returned by `split_results`.
+    pub fn to_routes(&self) -> Vec<Vec<(i32, i32)>> {
+        self.routes
+            .iter()
+            .map(|route| route.visits.clone())
+            .collect()
+    }
+
+    /// checks the routes against `instance`, see [`check_feasibility`], and the vehicle types of
+    /// the routes against the fleet of `instance`.
+    pub fn violations<T: AlkaidInstance + ?Sized>(&self, instance: &T) -> Vec<Violation> {
+        let mut violations = check_feasibility(instance, &self.to_routes());
+        let fleet = instance.fleet().unwrap_or_default();
+        let mut used = vec![0; fleet.len()];
+        for (route, vehicle_type) in self
+            .routes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, route)| route.vehicle_type.map(|vehicle_type| (i, vehicle_type)))
+        {
+            match fleet.get(vehicle_type) {
+                None => violations.push(Violation::VehicleTypeOutOfRange {
+                    route,
+                    vehicle_type,
+                }),
+                Some(vehicle) => {
+                    used[vehicle_type] += 1;
+                    if self.routes[route].peak_load > vehicle.capacity {
+                        violations.push(Violation::CapacityExceeded {
+                            route,
+                            load: self.routes[route].peak_load,
+                            capacity: vehicle.capacity,
+                        });
+                    }
+                }
+            }
+        }
+        for (vehicle_type, (&used, vehicle)) in used.iter().zip(fleet).enumerate() {
+            if used > vehicle.count {
+                violations.push(Violation::VehicleTypeExceeded {
+                    vehicle_type,
+                    used,
+                    count: vehicle.count,
+                });
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fleet::VehicleType;
+    use crate::instance::{InputFormat, Instance};
+
+    fn instance() -> Instance {
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+    }
+
+    #[test]
+    fn test_solution_metrics() {
+        let solution = Solution::new(
+            &instance(),
+            vec![vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)], vec![]],
+            0,
+        );
+        assert_eq!(solution.num_vehicles(), 2);
+        assert_eq!(solution.routes[0].load, 10);
+        assert_eq!(solution.routes[0].distance, 5 + 5 + 10);
+        assert_eq!(solution.routes[1].load, 8);
+        assert_eq!(solution.routes[1].distance, 10 + 7 + 5);
+        assert_eq!(solution.cost(), 42);
+        assert_eq!(
+            solution.split_customers().into_iter().collect::<Vec<_>>(),
+            vec![(2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_node_out_of_range() {
+        let instance = instance();
+        let solution = Solution::new(&instance, vec![vec![(1, 6), (4, 2)], vec![(-1, 2)]], 0);
+        assert_eq!(solution.routes[0].distance, 0);
+        assert_eq!(solution.routes[0].load, 8);
+        assert!(
+            solution
+                .violations(&instance)
+                .contains(&Violation::NodeOutOfRange {
+                    route: 1,
+                    position: 0,
+                    node: -1
+                })
+        );
+    }
+
+    #[test]
+    fn test_durations() {
+        let instance = instance().with_service_times(vec![2, 3, 4]).unwrap();
+        let routes = vec![vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]];
+        let solution = Solution::new(&instance, routes.clone(), 0);
+        assert_eq!(solution.routes[0].duration, 20 + 2 + 3);
+        assert_eq!(solution.routes[1].duration, 22 + 3 + 4);
+
+        let instance = instance
+            .with_time_windows(vec![(0, 29), (10, 20), (0, 15), (0, 100)])
+            .unwrap();
+        let solution = Solution::new(&instance, routes, 0);
+        // waits at customer 1 until its window opens at 10.
+        assert_eq!(solution.routes[0].arrival_times, vec![5, 17]);
+        assert_eq!(solution.routes[0].duration, 30);
+        assert_eq!(solution.routes[1].arrival_times, vec![10, 20]);
+    }
+
+    #[test]
+    fn test_route_duration() {
+        // an instance with a loading time of 10 at the depot on top of the schedule.
+        struct Loading(Instance);
+
+        impl AlkaidInstance for Loading {
+            fn capacity(&self) -> i32 {
+                self.0.capacity()
+            }
+
+            fn demands(&self) -> &[i32] {
+                self.0.demands()
+            }
+
+            fn input_format(&self) -> &InputFormat {
+                self.0.input_format()
+            }
+
+            fn route_duration(&self, customers: &[usize]) -> i32 {
+                self.0.route_duration(customers) + 10
+            }
+        }
+
+        let instance = Loading(instance());
+        let solution = Solution::new(&instance, vec![vec![(1, 6), (2, 4)]], 0);
+        assert_eq!(solution.routes[0].distance, 20);
+        assert_eq!(solution.routes[0].duration, 20 + 10);
+    }
+
+    #[test]
+    fn test_open_routes() {
+        let instance =
+            Instance::from_coord_list(10, vec![5, 5, 5], vec![(0, 0), (10, 0), (20, 0), (0, 30)])
+                .with_open_routes(true)
+                .unwrap();
+        let solution = Solution::new(&instance, vec![vec![(1, 5), (2, 5)], vec![(3, 5)]], 0);
+        assert_eq!(solution.routes[0].distance, 20);
+        assert_eq!(solution.routes[0].duration, 20);
+        assert_eq!(solution.routes[1].distance, 30);
+        assert_eq!(solution.cost(), 50);
+    }
+
+    #[test]
+    fn test_pickups() {
+        let instance = Instance::from_coord_list(10, vec![6, 4], vec![(0, 0), (10, 0), (20, 0)])
+            .with_pickups(vec![8, 0])
+            .unwrap();
+        let solution = Solution::new(
+            &instance,
+            vec![vec![(1, 6), (2, 4)], vec![(2, 4), (1, 6)]],
+            0,
+        );
+        assert_eq!(solution.routes[0].load, 10);
+        assert_eq!(solution.routes[0].peak_load, 12);
+        assert_eq!(solution.routes[1].peak_load, 10);
+    }
+
+    #[test]
+    fn test_unserved() {
+        let instance = Instance::from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0), (50, 0)])
+            .with_unserved_penalties(vec![100, 1])
+            .unwrap();
+        let solution = Solution::new(&instance, vec![vec![(1, 5), (2, 2)]], 0);
+        assert!(solution.violations(&instance).is_empty());
+        assert_eq!(solution.unserved(&instance), vec![(2, 3)]);
+        assert_eq!(solution.unserved_cost(&instance), 3);
+        assert_eq!(solution.penalized_cost(&instance), solution.cost() + 3);
+    }
+
+    #[test]
+    fn test_vehicle_types() {
+        let van = VehicleType {
+            fixed_cost: 10,
+            ..VehicleType::new(5, 2)
+        };
+        let truck = VehicleType {
+            fixed_cost: 30,
+            distance_multiplier: 1.5,
+            ..VehicleType::new(12, 1)
+        };
+        let instance =
+            Instance::from_coord_list(10, vec![10, 4, 3], vec![(0, 0), (3, 4), (0, 5), (-4, 3)])
+                .with_fleet(vec![van, truck])
+                .unwrap();
+        let mut solution = Solution::new(
+            &instance,
+            vec![vec![(1, 10)], vec![(2, 4)], vec![(3, 3)]],
+            0,
+        );
+        for (route, vehicle_type) in solution.routes.iter_mut().zip([1, 0, 0]) {
+            route.vehicle_type = Some(vehicle_type);
+        }
+        assert!(solution.violations(&instance).is_empty());
+        assert_eq!(
+            solution.fleet_cost(&instance),
+            (30 + 15) + (10 + 10) + (10 + 10)
+        );
+        solution.routes[0].vehicle_type = Some(0);
+        assert_eq!(
+            solution.violations(&instance),
+            vec![
+                Violation::CapacityExceeded {
+                    route: 0,
+                    load: 10,
+                    capacity: 5
+                },
+                Violation::VehicleTypeExceeded {
+                    vehicle_type: 0,
+                    used: 3,
+                    count: 2
+                },
+            ]
+        );
+        solution.routes[0].vehicle_type = Some(2);
+        assert_eq!(
+            solution.violations(&instance)[0],
+            Violation::VehicleTypeOutOfRange {
+                route: 0,
+                vehicle_type: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::error::ParseError;
+use crate::instance::{Instance, tsplib_geo};
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    NodeCoord,
+    Demand,
+    Depot,
+    EdgeWeight,
+    Ignored,
+}
+
+#[derive(Default)]
+struct VrpData {
+    dimension: Option<usize>,
+    capacity: Option<i32>,
+    edge_weight_type: Option<String>,
+    edge_weight_format: Option<String>,
+    coords: Vec<(usize, f64, f64)>,
+    demands: Vec<(usize, i32)>,
+    depots: Vec<usize>,
+    edge_weights: Vec<i32>,
+}
+
+pub(crate) fn syntax_error(line: usize, message: impl Into<String>) -> ParseError {
+    ParseError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+fn parse_token<T: std::str::FromStr>(line: usize, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| syntax_error(line, format!("invalid number `{}`", token)))
+}
+
+/// parses a node id, which counts from `1` in the file, into an index counting from `0`.
+fn parse_node(line: usize, token: &str, dimension: usize) -> Result<usize, ParseError> {
+    let id = parse_token::<usize>(line, token)?;
+    if id == 0 || id > dimension {
+        return Err(syntax_error(line, format!("node {} out of range", id)));
+    }
+    Ok(id - 1)
+}
+
+impl VrpData {
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        let mut data = Self::default();
+        let mut section = Section::None;
+        for (i, raw_line) in s.lines().enumerate() {
+            let line = i + 1;
+            let text = raw_line.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if text.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                let (key, value) = match text.split_once(':') {
+                    Some((key, value)) => (key.trim().to_ascii_uppercase(), value.trim()),
+                    None => (text.to_ascii_uppercase(), ""),
+                };
+                section = Section::None;
+                match key.as_str() {
+                    "EOF" => break,
+                    "DIMENSION" => data.dimension = Some(parse_token(line, value)?),
+                    "CAPACITY" => data.capacity = Some(parse_token(line, value)?),
+                    "EDGE_WEIGHT_TYPE" => data.edge_weight_type = Some(value.to_ascii_uppercase()),
+                    "EDGE_WEIGHT_FORMAT" => {
+                        data.edge_weight_format = Some(value.to_ascii_uppercase())
+                    }
+                    "NODE_COORD_SECTION" => section = Section::NodeCoord,
+                    "DEMAND_SECTION" => section = Section::Demand,
+                    "DEPOT_SECTION" => section = Section::Depot,
+                    "EDGE_WEIGHT_SECTION" => section = Section::EdgeWeight,
+                    _ if key.ends_with("_SECTION") => section = Section::Ignored,
+                    _ => {}
+                }
+                continue;
+            }
+            let dimension = data
+                .dimension
+                .ok_or_else(|| syntax_error(line, "data before DIMENSION"))?;
+            let tokens = text.split_whitespace().collect::<Vec<_>>();
+            match section {
+                Section::NodeCoord => {
+                    if tokens.len() != 3 {
+                        return Err(syntax_error(line, "expected `id x y`"));
+                    }
+                    data.coords.push((
+                        parse_node(line, tokens[0], dimension)?,
+                        parse_token(line, tokens[1])?,
+                        parse_token(line, tokens[2])?,
+                    ));
+                }
+                Section::Demand => {
+                    if tokens.len() != 2 {
+                        return Err(syntax_error(line, "expected `id demand`"));
+                    }
+                    data.demands.push((
+                        parse_node(line, tokens[0], dimension)?,
+                        parse_token(line, tokens[1])?,
+                    ));
+                }
+                Section::Depot => {
+                    for token in tokens {
+                        if token == "-1" {
+                            section = Section::Ignored;
+                            break;
+                        }
+                        data.depots.push(parse_node(line, token, dimension)?);
+                    }
+                }
+                Section::EdgeWeight => {
+                    for token in tokens {
+                        data.edge_weights.push(parse_token(line, token)?);
+                    }
+                }
+                Section::Ignored => {}
+                Section::None => return Err(syntax_error(line, "data outside of a section")),
+            }
+        }
+        Ok(data)
+    }
+
+    /// the full distance matrix in file order.
+    fn distance_matrix(&self, dimension: usize) -> Result<Vec<Vec<i32>>, ParseError> {
+        let edge_weight_type = self
+            .edge_weight_type
+            .as_deref()
+            .ok_or_else(|| ParseError::Missing("EDGE_WEIGHT_TYPE".to_owned()))?;
+        if edge_weight_type == "EXPLICIT" {
+            return self.explicit_matrix(dimension);
+        }
+        let distance: fn((f64, f64), (f64, f64)) -> i32 = match edge_weight_type {
+            "EUC_2D" => euc_2d,
+            "CEIL_2D" => ceil_2d,
+            "ATT" => att,
+            "GEO" => geo,
+            other => {
+                return Err(ParseError::Unsupported(format!(
+                    "EDGE_WEIGHT_TYPE {}",
+                    other
+                )));
+            }
+        };
+        let mut coords = vec![None; dimension];
+        for &(node, x, y) in &self.coords {
+            coords[node] = Some((x, y));
+        }
+        let coords = coords
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| {
+                c.ok_or_else(|| ParseError::Missing(format!("coordinates of node {}", i + 1)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((0..dimension)
+            .map(|i| {
+                (0..dimension)
+                    .map(|j| {
+                        if i == j {
+                            0
+                        } else {
+                            distance(coords[i], coords[j])
+                        }
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    fn explicit_matrix(&self, dimension: usize) -> Result<Vec<Vec<i32>>, ParseError> {
+        let format = self
+            .edge_weight_format
+            .as_deref()
+            .ok_or_else(|| ParseError::Missing("EDGE_WEIGHT_FORMAT".to_owned()))?;
+        let entries: Vec<(usize, usize)> = match format {
+            "FULL_MATRIX" => (0..dimension)
+                .flat_map(|i| (0..dimension).map(move |j| (i, j)))
+                .collect(),
+            "LOWER_ROW" => (0..dimension)
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .collect(),
+            "UPPER_ROW" => (0..dimension)
+                .flat_map(|i| (i + 1..dimension).map(move |j| (i, j)))
+                .collect(),
+            "LOWER_DIAG_ROW" => (0..dimension)
+                .flat_map(|i| (0..=i).map(move |j| (i, j)))
+                .collect(),
+            other => {
+                return Err(ParseError::Unsupported(format!(
+                    "EDGE_WEIGHT_FORMAT {}",
+                    other
+                )));
+            }
+        };
+        if entries.len() != self.edge_weights.len() {
+            return Err(ParseError::Missing(format!(
+                "edge weights, expected {} but found {}",
+                entries.len(),
+                self.edge_weights.len()
+            )));
+        }
+        let mut matrix = vec![vec![0; dimension]; dimension];
+        for (&(i, j), &weight) in entries.iter().zip(&self.edge_weights) {
+            matrix[i][j] = weight;
+            if format != "FULL_MATRIX" {
+                matrix[j][i] = weight;
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+/// rounds to the nearest integer as TSPLIB's `nint`.
+fn nint(x: f64) -> i32 {
+    (x + 0.5).floor() as i32
+}
+
+fn euc_2d(a: (f64, f64), b: (f64, f64)) -> i32 {
+    nint((a.0 - b.0).hypot(a.1 - b.1))
+}
+
+fn ceil_2d(a: (f64, f64), b: (f64, f64)) -> i32 {
+    (a.0 - b.0).hypot(a.1 - b.1).ceil() as i32
+}
+
+fn att(a: (f64, f64), b: (f64, f64)) -> i32 {
+    let r = (((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)) / 10.0).sqrt();
+    let t = nint(r);
+    if (t as f64) < r { t + 1 } else { t }
+}
+
+/// the TSPLIB `GEO` distance, where `x` is the latitude and `y` the longitude.
+fn geo(a: (f64, f64), b: (f64, f64)) -> i32 {
+    (tsplib_geo(a, b) + 1.0) as i32
+}
+
+impl Instance {
+    /// reads an instance in the TSPLIB / CVRPLIB `.vrp` format.
+    ///
+    /// supports the `EUC_2D`, `CEIL_2D`, `ATT`, `GEO` and `EXPLICIT` edge weight types, the latter
+    /// with the `FULL_MATRIX`, `LOWER_ROW`, `UPPER_ROW` and `LOWER_DIAG_ROW` formats. the depot becomes
+    /// node `0` and the customers keep the order of the file, so customer `i` of the instance is the
+    /// `i`-th non-depot node of the file.
+    pub fn from_vrp_str(s: &str) -> Result<Self, ParseError> {
+        let data = VrpData::parse(s)?;
+        let dimension = data
+            .dimension
+            .ok_or_else(|| ParseError::Missing("DIMENSION".to_owned()))?;
+        let capacity = data
+            .capacity
+            .ok_or_else(|| ParseError::Missing("CAPACITY".to_owned()))?;
+        let depot = match data.depots.as_slice() {
+            [] => 0,
+            &[depot] => depot,
+            _ => return Err(ParseError::Unsupported("multiple depots".to_owned())),
+        };
+        let mut demands = vec![None; dimension];
+        for &(node, demand) in &data.demands {
+            demands[node] = Some(demand);
+        }
+        let order = std::iter::once(depot)
+            .chain((0..dimension).filter(|&i| i != depot))
+            .collect::<Vec<_>>();
+        let demands = order[1..]
+            .iter()
+            .map(|&i| {
+                demands[i].ok_or_else(|| ParseError::Missing(format!("demand of node {}", i + 1)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let matrix = data.distance_matrix(dimension)?;
+        let matrix = order
+            .iter()
+            .map(|&i| order.iter().map(|&j| matrix[i][j]).collect())
+            .collect();
+        Ok(Self::try_from_dense_matrix(capacity, demands, matrix)?)
+    }
+
+    /// reads an instance from a file in the TSPLIB / CVRPLIB `.vrp` format, see [`Instance::from_vrp_str`].
+    pub fn from_vrp_file<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::from_vrp_str(&std::fs::read_to_string(path)?)
+    }
+}
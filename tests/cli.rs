@@ -0,0 +1,102 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn sdvrp(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sdvrp"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// writes a small instance to a file of the temporary directory named after the test.
+fn instance_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("sdvrp-cli-{}.vrp", name));
+    std::fs::write(
+        &path,
+        "NAME : toy
+TYPE : CVRP
+DIMENSION : 3
+EDGE_WEIGHT_TYPE : EUC_2D
+CAPACITY : 10
+NODE_COORD_SECTION
+1 0 0
+2 3 4
+3 6 8
+DEMAND_SECTION
+1 0
+2 6
+3 8
+DEPOT_SECTION
+1
+-1
+EOF
+",
+    )
+    .unwrap();
+    path
+}
+
+#[test]
+fn test_help() {
+    let output = sdvrp(&["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Usage: sdvrp"));
+}
+
+#[test]
+fn test_usage_errors() {
+    let output = sdvrp(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("missing INSTANCE"));
+
+    let output = sdvrp(&["x.vrp", "--frobnicate"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("unknown option `--frobnicate`"));
+
+    let output = sdvrp(&["x.vrp", "--time-limit"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--time-limit requires a value"));
+}
+
+#[test]
+fn test_input_errors() {
+    let output = sdvrp(&["does-not-exist.vrp"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("does-not-exist.vrp"));
+
+    let instance = instance_file("input-errors");
+    let output = sdvrp(&[instance.to_str().unwrap(), "--acceptance", "LAHC:x"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("invalid value `x` for --acceptance"));
+}
+
+#[test]
+fn test_names_ignore_case() {
+    let instance = instance_file("names-ignore-case");
+    let instance = instance.to_str().unwrap();
+    // the invalid values are only rejected by the validation of the config, which runs after the
+    // names are parsed and before the solver is started.
+    let output = sdvrp(&[instance, "--acceptance", "lahc:0"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("LAHC length must be positive"));
+
+    let output = sdvrp(&[instance, "--ruin-method", "random:0"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("Random ruin sizes must be positive"));
+
+    let output = sdvrp(&[
+        instance,
+        "--inter-operators",
+        "relocate",
+        "--time-limit",
+        "0",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("time_limit must be positive"));
+}
@@ -1,34 +1,34 @@
 use sdvrp::{
-    config,
-    instance::{AlkaidInstance, InputFormat::*, Instance},
-    solve_sdvrp,
+    CancellationToken, Depot, InstanceError, MultiDepotInstance, Solution, SolveError,
+    SolveListener, VehicleType, Violation, check_feasibility, config,
+    instance::{AlkaidInstance, DistanceRounding, DistanceUnit, GeoMetric, InputFormat, Instance},
+    solve_multi_depot, solve_sdvrp, solve_sdvrp_with_initial_solution, solve_sdvrp_with_listener,
 };
 
-fn calc_cost(solution: &[Vec<(i32, i32)>], instance: &Instance) -> i32 {
-    let dist: &dyn Fn((usize, usize)) -> i32 = match instance.input_format() {
-        DenseMatrix(items) => &|(a, b)| items[a][b],
-        CoordList(items) => &|(a, b)| {
-            (((items[a].0 - items[b].0).pow(2) + (items[a].1 - items[b].1).pow(2)) as f64).sqrt()
-                as i32
-        },
-    };
-    let mut demands = instance.demands().to_owned();
+/// a short search, enough for the small instances below.
+fn config() -> config::Config {
+    config::Config {
+        time_limit: 0.1,
+        ..Default::default()
+    }
+}
+
+fn calc_cost(solution: &Solution, instance: &Instance) -> i32 {
+    let dist = |(a, b)| instance.distance(a, b);
+    let violations = check_feasibility(instance, &solution.to_routes());
+    assert!(
+        violations.is_empty(),
+        "infeasible solution: {:?}",
+        violations
+    );
     let mut total_cost = 0;
-    for route in solution {
-        assert!(
-            route.iter().map(|(_, x)| *x).sum::<i32>() <= instance.capacity(),
-            "capacity exceeded"
-        );
-        for &(i, load) in route {
-            demands[i as usize - 1] -= load;
-        }
+    for route in solution.routes.iter().map(|route| &route.visits) {
         route
             .windows(2)
             .for_each(|w| total_cost += dist((w[0].0 as usize, w[1].0 as usize)));
         total_cost += dist((0, route.first().unwrap().0 as usize));
         total_cost += dist((route.last().unwrap().0 as usize, 0));
     }
-    assert!(demands.iter().all(|&x| x == 0), "all demands not served");
     total_cost
 }
 
@@ -52,11 +52,13 @@ fn test_dense_matrix() {
     );
     let mut config = config::Config::default();
     config.time_limit = 0.1;
-    let solution = solve_sdvrp(&config, &instance);
+    let solution = solve_sdvrp(&config, &instance).unwrap();
     assert!(
         calc_cost(&solution, &instance) <= 2282842,
         "cost should be less than 2282842"
     );
+    assert_eq!(solution.cost(), calc_cost(&solution, &instance));
+    assert_eq!(solution.objective, solution.cost());
     println!("{:?}", solution);
 }
 
@@ -85,10 +87,765 @@ fn test_coord_list() {
     );
     let mut config = config::Config::default();
     config.time_limit = 5.0;
-    let solution = solve_sdvrp(&config, &instance);
+    let solution = solve_sdvrp(&config, &instance).unwrap();
     assert!(
         calc_cost(&solution, &instance) <= 268475,
         "cost should be less than 268475"
     );
+    assert_eq!(solution.objective, solution.cost());
     println!("{:?}", solution);
 }
+
+#[derive(Default)]
+struct RecordingListener {
+    started: bool,
+    updates: Vec<i32>,
+    costs: Vec<i32>,
+    end: Option<(Solution, i32)>,
+}
+
+impl SolveListener for RecordingListener {
+    fn on_start(&mut self) {
+        self.started = true;
+    }
+
+    fn on_updated(&mut self, solution: &Solution, objective: i32) {
+        self.updates.push(objective);
+        self.costs.push(solution.cost());
+    }
+
+    fn on_end(&mut self, solution: &Solution, objective: i32) {
+        self.end = Some((solution.clone(), objective));
+    }
+}
+
+#[test]
+fn test_listener() {
+    let instance = Instance::from_coord_list(
+        10,
+        vec![6, 8, 4, 7],
+        vec![(0, 0), (3, 4), (6, 8), (0, 5), (-4, 3)],
+    );
+    let config = config();
+    let mut listener = RecordingListener::default();
+    let solution = solve_sdvrp_with_listener(&config, &instance, &mut listener).unwrap();
+    assert!(listener.started);
+    assert_eq!(listener.costs, listener.updates);
+    assert!(listener.updates.windows(2).all(|w| w[1] <= w[0]));
+    assert_eq!(listener.end, Some((solution.clone(), solution.objective)));
+}
+
+struct PanickingListener;
+
+impl SolveListener for PanickingListener {
+    fn on_updated(&mut self, _solution: &Solution, _objective: i32) {
+        panic!("listener failed");
+    }
+}
+
+#[test]
+#[should_panic(expected = "listener failed")]
+fn test_listener_panic() {
+    let instance =
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)]);
+    let config = config();
+    solve_sdvrp_with_listener(&config, &instance, &mut PanickingListener).unwrap();
+}
+
+#[test]
+fn test_distance_rounding() {
+    let coords = vec![(0.0, 0.0), (1.5, 2.0), (0.0, 2.25)];
+    let distance = |rounding| {
+        let instance =
+            Instance::from_coord_list_with_rounding(10, vec![6, 8], coords.clone(), rounding);
+        (instance.distance(0, 1), instance.distance(1, 2))
+    };
+    assert_eq!(distance(DistanceRounding::Round), (3, 2));
+    assert_eq!(distance(DistanceRounding::Floor), (2, 1));
+    assert_eq!(distance(DistanceRounding::Ceil), (3, 2));
+    assert_eq!(distance(DistanceRounding::Scaled(100.0)), (250, 152));
+    assert_eq!(
+        Instance::try_from_coord_list_with_rounding(
+            10,
+            vec![6, 8],
+            coords.clone(),
+            DistanceRounding::Scaled(0.0)
+        )
+        .err(),
+        Some(InstanceError::InvalidScale(0.0))
+    );
+    assert_eq!(
+        Instance::try_from_coord_list(
+            10,
+            vec![6, 8],
+            vec![(0.0, 0.0), (f64::NAN, 1.0), (1.0, 1.0)]
+        )
+        .err(),
+        Some(InstanceError::NonFiniteCoordinate { node: 1 })
+    );
+    assert_eq!(
+        Instance::try_from_coord_list_with_rounding(
+            10,
+            vec![6, 8],
+            vec![(0.0, 0.0), (1e6, 0.0), (0.0, 0.0)],
+            DistanceRounding::Scaled(1e4)
+        )
+        .err(),
+        Some(InstanceError::DistanceOverflow { distance: 1e6 })
+    );
+
+    let instance = Instance::from_coord_list_with_rounding(
+        10,
+        vec![6, 8],
+        coords,
+        DistanceRounding::Scaled(100.0),
+    );
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert_eq!(solution.objective, calc_cost(&solution, &instance));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_legacy_coord_list() {
+    let input = InputFormat::CoordList(vec![(0, 0), (3, 4), (1, 1)]);
+    assert_eq!(input.to_coord_list_x(), vec![0, 3, 1]);
+    assert_eq!(input.to_coord_list_y(), vec![0, 4, 1]);
+    assert_eq!(input.to_dense_matrix(), vec![0, 5, 1, 5, 0, 4, 1, 4, 0]);
+}
+
+#[test]
+fn test_lat_lon() {
+    // Paris, London and the Louvre.
+    let coords = vec![(48.8566, 2.3522), (51.5074, -0.1278), (48.8606, 2.3376)];
+    let distance = |unit, rounding| {
+        let instance = Instance::from_lat_lon(
+            10,
+            vec![6, 8],
+            coords.clone(),
+            GeoMetric::Haversine,
+            unit,
+            rounding,
+        );
+        (instance.distance(0, 1), instance.distance(0, 2))
+    };
+    assert_eq!(
+        distance(DistanceUnit::Metres, DistanceRounding::Round),
+        (343557, 1157)
+    );
+    assert_eq!(
+        distance(DistanceUnit::Kilometres, DistanceRounding::Round),
+        (344, 1)
+    );
+    assert_eq!(
+        distance(DistanceUnit::Kilometres, DistanceRounding::Scaled(10.0)),
+        (3436, 12)
+    );
+    assert_eq!(
+        distance(DistanceUnit::Miles, DistanceRounding::Floor),
+        (213, 0)
+    );
+
+    let instance = Instance::from_lat_lon(
+        10,
+        vec![6],
+        vec![(48.52, 2.20), (51.30, 0.07)],
+        GeoMetric::TsplibGeo,
+        DistanceUnit::Kilometres,
+        DistanceRounding::Ceil,
+    );
+    assert_eq!(instance.distance(0, 1), 333);
+    assert_eq!(instance.distance(1, 1), 0);
+
+    assert_eq!(
+        Instance::try_from_lat_lon(
+            10,
+            vec![6],
+            vec![(48.8566, 2.3522), (2.3522, 248.8566)],
+            GeoMetric::Haversine,
+            DistanceUnit::Metres,
+            DistanceRounding::Round,
+        )
+        .err(),
+        Some(InstanceError::LatLonOutOfRange {
+            node: 1,
+            latitude: 2.3522,
+            longitude: 248.8566
+        })
+    );
+}
+
+#[test]
+fn test_invalid_config() {
+    let instance = Instance::from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0), (0, 1)]);
+    let mut config = config::Config {
+        time_limit: -1.0,
+        ..Default::default()
+    };
+    assert!(matches!(
+        solve_sdvrp(&config, &instance),
+        Err(SolveError::InvalidConfig(_))
+    ));
+    config.time_limit = 0.1;
+    config.acceptance_rule_type = config::AcceptanceRuleType::LAHC(0);
+    assert!(matches!(
+        solve_sdvrp(&config, &instance),
+        Err(SolveError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_invalid_instance() {
+    let config = config();
+    let instance = Instance::try_from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0), (0, 1)]);
+    assert!(solve_sdvrp(&config, &instance.unwrap()).is_ok());
+    assert_eq!(
+        Instance::try_from_coord_list(0, vec![5, 5], vec![(0, 0), (1, 0), (0, 1)]).err(),
+        Some(InstanceError::NonPositiveCapacity(0))
+    );
+    assert_eq!(
+        Instance::try_from_coord_list(10, vec![5, -5], vec![(0, 0), (1, 0), (0, 1)]).err(),
+        Some(InstanceError::NonPositiveDemand {
+            customer: 2,
+            demand: -5
+        })
+    );
+    assert_eq!(
+        Instance::try_from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0)]).err(),
+        Some(InstanceError::NodeCountMismatch {
+            expected: 3,
+            found: 2
+        })
+    );
+    assert_eq!(
+        Instance::try_from_dense_matrix(10, vec![5], vec![vec![0, 1], vec![1]]).err(),
+        Some(InstanceError::RowLengthMismatch {
+            row: 1,
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(
+        Instance::try_from_dense_matrix(10, vec![5], vec![vec![1, 1], vec![1, 0]]).err(),
+        Some(InstanceError::NonZeroDiagonal {
+            node: 0,
+            distance: 1
+        })
+    );
+    assert_eq!(
+        Instance::try_from_dense_matrix(10, vec![5], vec![vec![0, -1], vec![-1, 0]]).err(),
+        Some(InstanceError::NegativeDistance {
+            from: 0,
+            to: 1,
+            distance: -1
+        })
+    );
+}
+
+#[test]
+fn test_asymmetric_distances() {
+    // the tour 0 -> 1 -> 2 -> 3 -> 0 costs 4, travelling it backwards costs 40.
+    #[rustfmt::skip]
+    let instance = Instance::from_dense_matrix(
+        10,
+        vec![3, 3, 3],
+        vec![
+            vec![0, 1, 10, 10],
+            vec![10, 0, 1, 10],
+            vec![10, 10, 0, 1],
+            vec![1, 10, 10, 0],
+        ],
+    );
+    assert_eq!(instance.distance(0, 1), 1);
+    assert_eq!(instance.distance(1, 0), 10);
+    // a config whose intra operators all assume symmetric distances is rejected.
+    let only_symmetric = config::Config {
+        intra_operators: vec![
+            config::IntraOperators::OrOpt2,
+            config::IntraOperators::OrOpt3,
+        ],
+        ..config()
+    };
+    assert!(matches!(
+        solve_sdvrp(&only_symmetric, &instance),
+        Err(SolveError::InvalidConfig(_))
+    ));
+    // the default config runs, without the operators that assume symmetric distances.
+    let config = config();
+    assert!(config::validate_asymmetric(&config).is_err());
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert_eq!(solution.to_routes(), vec![vec![(1, 3), (2, 3), (3, 3)]]);
+    assert_eq!(solution.objective, 4);
+    assert_eq!(calc_cost(&solution, &instance), 4);
+}
+
+#[test]
+fn test_max_vehicles() {
+    // serving both customers from one route costs 12, two routes cost 4.
+    let instance = Instance::from_dense_matrix(
+        10,
+        vec![5, 5],
+        vec![vec![0, 1, 1], vec![1, 0, 10], vec![1, 10, 0]],
+    );
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert_eq!(solution.num_vehicles(), 2);
+    assert_eq!(solution.objective, 4);
+
+    let instance = instance.with_max_vehicles(1).unwrap();
+    assert_eq!(instance.max_vehicles(), Some(1));
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert_eq!(solution.num_vehicles(), 1);
+    assert_eq!(solution.objective, 12);
+    assert_eq!(calc_cost(&solution, &instance), 12);
+
+    // a route of both customers is too long, so one vehicle is not enough.
+    let limited = instance.clone().with_max_route_distance(2).unwrap();
+    match solve_sdvrp(&config, &limited) {
+        Err(SolveError::FleetExceeded {
+            vehicles: 2,
+            max_vehicles: 1,
+            solution,
+        }) => assert_eq!(solution.num_vehicles(), 2),
+        other => panic!("expected FleetExceeded, got {:?}", other),
+    }
+
+    assert_eq!(
+        instance.with_max_vehicles(0).err(),
+        Some(InstanceError::InsufficientFleet {
+            total_demand: 10,
+            fleet_capacity: 0
+        })
+    );
+}
+
+#[test]
+fn test_heterogeneous_fleet() {
+    let van = VehicleType {
+        fixed_cost: 10,
+        ..VehicleType::new(5, 2)
+    };
+    let truck = VehicleType {
+        fixed_cost: 30,
+        distance_multiplier: 1.5,
+        ..VehicleType::new(12, 1)
+    };
+    let instance =
+        Instance::from_coord_list(10, vec![10, 4, 3], vec![(0, 0), (3, 4), (0, 5), (-4, 3)])
+            .with_fleet(vec![van.clone(), truck])
+            .unwrap();
+    assert_eq!(instance.capacity(), 12);
+
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert!(
+        solution
+            .routes
+            .iter()
+            .all(|route| route.vehicle_type.is_some())
+    );
+    assert_eq!(solution.objective as i64, solution.fleet_cost(&instance));
+
+    assert_eq!(
+        Instance::from_coord_list(10, vec![10, 4, 3], vec![(0, 0), (3, 4), (0, 5), (-4, 3)])
+            .with_fleet(vec![van])
+            .err(),
+        Some(InstanceError::InsufficientFleet {
+            total_demand: 17,
+            fleet_capacity: 10
+        })
+    );
+}
+
+#[test]
+fn test_route_limits() {
+    let instance =
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+            .with_service_times(vec![2, 3, 4])
+            .unwrap();
+    let instance = instance
+        .with_max_route_distance(21)
+        .unwrap()
+        .with_max_route_duration(28)
+        .unwrap();
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(solution.objective, calc_cost(&solution, &instance));
+    // {1: 6, 3: 4} and {2: 8} are within the limits.
+    assert_eq!(solution.num_vehicles(), 2);
+
+    assert_eq!(
+        instance.clone().with_max_route_distance(19).err(),
+        Some(InstanceError::UnreachableCustomer { customer: 2 })
+    );
+    assert_eq!(
+        instance.with_service_times(vec![1, 2]).err(),
+        Some(InstanceError::ServiceTimeCountMismatch {
+            expected: 3,
+            found: 2
+        })
+    );
+}
+
+#[test]
+fn test_time_windows() {
+    let instance =
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+            .with_service_times(vec![2, 3, 4])
+            .unwrap()
+            .with_time_windows(vec![(0, 29), (10, 20), (0, 15), (0, 100)])
+            .unwrap();
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(solution.objective, calc_cost(&solution, &instance));
+    for route in &solution.routes {
+        assert_eq!(route.arrival_times.len(), route.visits.len());
+    }
+    // {3: 4, 1: 6} and {2: 8} are on time.
+    assert_eq!(solution.num_vehicles(), 2);
+
+    let travel_times = (0..4)
+        .map(|i| (0..4).map(|j| 2 * instance.distance(i, j)).collect())
+        .collect();
+    assert_eq!(
+        instance.clone().with_travel_times(travel_times).err(),
+        Some(InstanceError::UnreachableCustomer { customer: 2 })
+    );
+    assert_eq!(
+        instance.clone().with_time_windows(vec![(0, 29)]).err(),
+        Some(InstanceError::TimeWindowCountMismatch {
+            expected: 4,
+            found: 1
+        })
+    );
+    assert_eq!(
+        instance
+            .with_time_windows(vec![(0, 29), (10, 5), (0, 15), (0, 100)])
+            .err(),
+        Some(InstanceError::InvalidTimeWindow {
+            node: 1,
+            earliest: 10,
+            latest: 5
+        })
+    );
+}
+
+#[test]
+fn test_split_policy() {
+    let instance =
+        Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+            .with_max_visits(vec![2, 1, 2])
+            .unwrap()
+            .with_min_delivery(5)
+            .unwrap();
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(solution.objective, calc_cost(&solution, &instance));
+
+    // the two vehicles are full, so customer 3 only fits a route after load of another customer
+    // moves out of it, e.g. {3: 8, 1: 2} and {1: 4, 2: 6}.
+    let full = Instance::from_coord_list(10, vec![6, 6, 8], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+        .with_max_visits(vec![2, 2, 1])
+        .unwrap()
+        .with_max_vehicles(2)
+        .unwrap();
+    let solution = solve_sdvrp(&config, &full).unwrap();
+    assert!(solution.violations(&full).is_empty());
+    assert_eq!(solution.num_vehicles(), 2);
+
+    assert_eq!(
+        instance.clone().with_max_visits(vec![2, 0, 2]).err(),
+        Some(InstanceError::ZeroMaxVisits { customer: 2 })
+    );
+    let instance =
+        Instance::from_coord_list(10, vec![6, 15, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)]);
+    assert_eq!(
+        instance.clone().with_max_visits(vec![2, 1, 2]).err(),
+        Some(InstanceError::UnsplittableDemand { customer: 2 })
+    );
+    assert_eq!(
+        instance.with_min_delivery(9).err(),
+        Some(InstanceError::UnsplittableDemand { customer: 2 })
+    );
+}
+
+#[test]
+fn test_multi_depot() {
+    let coords = vec![
+        (0.0, 0.0),
+        (20.0, 0.0),
+        (2.0, 1.0),
+        (3.0, -1.0),
+        (18.0, 1.0),
+        (19.0, -2.0),
+    ];
+    let input = InputFormat::Planar {
+        coords,
+        rounding: DistanceRounding::Round,
+    };
+    let depots = vec![
+        Depot {
+            max_vehicles: Some(1)
+        };
+        2
+    ];
+    let instance = MultiDepotInstance::new(10, vec![4, 5, 6, 3], depots.clone(), input.clone());
+    let config = config();
+    let solution = solve_multi_depot(&config, &instance, &mut ()).unwrap();
+    assert!(instance.violations(&solution).is_empty());
+    assert_eq!(solution.routes.len(), 2);
+    assert_eq!(
+        solution.objective,
+        solution
+            .routes
+            .iter()
+            .map(|route| route.distance)
+            .sum::<i32>()
+    );
+
+    // all customers are nearest to depot 0, whose vehicle only has room for one and a part.
+    let crowded = MultiDepotInstance::new(
+        10,
+        vec![6, 6, 6],
+        depots.clone(),
+        InputFormat::Planar {
+            coords: vec![(0.0, 0.0), (20.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)],
+            rounding: DistanceRounding::Round,
+        },
+    );
+    let solution = solve_multi_depot(&config, &crowded, &mut ()).unwrap();
+    assert!(crowded.violations(&solution).is_empty());
+
+    assert_eq!(
+        MultiDepotInstance::try_new(10, vec![4, 5, 6, 3], vec![], input.clone()).err(),
+        Some(InstanceError::NoDepot)
+    );
+    assert_eq!(
+        MultiDepotInstance::try_new(10, vec![4, 5, 6], depots, input).err(),
+        Some(InstanceError::NodeCountMismatch {
+            expected: 5,
+            found: 6
+        })
+    );
+}
+
+#[test]
+fn test_open_routes() {
+    let instance =
+        Instance::from_coord_list(10, vec![5, 5, 5], vec![(0, 0), (10, 0), (20, 0), (0, 30)])
+            .with_open_routes(true)
+            .unwrap();
+    let config = config();
+    // the free legs back to the depot make the distances asymmetric, so the default operators
+    // that assume symmetry are left out.
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(solution.objective, 50);
+    assert_eq!(solution.objective, solution.cost());
+}
+
+#[test]
+fn test_unserved_penalties() {
+    // customer 2 is 100 away there and back, but leaving it unserved costs 5.
+    let instance = Instance::from_coord_list(10, vec![5, 5], vec![(0, 0), (1, 0), (50, 0)])
+        .with_unserved_penalties(vec![100, 1])
+        .unwrap();
+    let config = config();
+    let mut listener = RecordingListener::default();
+    let solution = solve_sdvrp_with_listener(&config, &instance, &mut listener).unwrap();
+    assert_eq!(solution.to_routes(), vec![vec![(1, 5)]]);
+    assert_eq!(solution.unserved(&instance), vec![(2, 5)]);
+    assert_eq!(solution.objective, 2 + 5);
+    // the search serves customer 2, the end reports the solution without it.
+    assert_eq!(listener.updates.last(), Some(&100));
+    assert_eq!(listener.end, Some((solution.clone(), solution.objective)));
+
+    // the fleet cannot carry both customers, so the one with the smaller penalty is served in
+    // part: {1: 8, 2: 2} costs 3 and leaves 6 unserved at 2 each.
+    let instance = Instance::from_coord_list(10, vec![8, 8], vec![(0, 0), (1, 0), (0, 1)])
+        .with_unserved_penalties(vec![3, 2])
+        .unwrap()
+        .with_max_vehicles(1)
+        .unwrap();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(solution.unserved(&instance), vec![(2, 6)]);
+    assert_eq!(solution.objective, 3 + 6 * 2);
+
+    assert_eq!(
+        instance.with_unserved_penalties(vec![3, -1]).err(),
+        Some(InstanceError::NegativeUnservedPenalty {
+            customer: 2,
+            penalty: -1
+        })
+    );
+}
+
+#[test]
+fn test_pickups() {
+    let instance = Instance::from_coord_list(10, vec![6, 4], vec![(0, 0), (10, 0), (20, 0)])
+        .with_pickups(vec![8, 0])
+        .unwrap();
+    let config = config();
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(solution.objective, calc_cost(&solution, &instance));
+    // the search sees a single route either way, which is reordered to {2: 4, 1: 6}.
+    assert_eq!(solution.objective, 40);
+
+    assert_eq!(
+        instance.clone().with_pickups(vec![11, 0]).err(),
+        Some(InstanceError::InvalidPickup {
+            customer: 1,
+            pickup: 11
+        })
+    );
+    assert_eq!(
+        Instance::from_coord_list(10, vec![12, 4], vec![(0, 0), (10, 0), (20, 0)])
+            .with_pickups(vec![1, 0])
+            .err(),
+        Some(InstanceError::UnsplittableDemand { customer: 1 })
+    );
+}
+
+/// cancels its token on the first update, and stops the search once it is cancelled.
+struct Canceller(CancellationToken);
+
+impl SolveListener for Canceller {
+    fn on_updated(&mut self, _solution: &Solution, _objective: i32) {
+        self.0.cancel();
+    }
+
+    fn should_stop(&mut self) -> bool {
+        self.0.should_stop()
+    }
+}
+
+#[test]
+fn test_cancellation() {
+    let instance = Instance::from_coord_list(
+        10,
+        vec![6, 8, 4, 7],
+        vec![(0, 0), (3, 4), (6, 8), (0, 5), (-4, 3)],
+    );
+    let config = config::Config {
+        time_limit: 60.0,
+        ..Default::default()
+    };
+    // cancels as soon as there is a solution, like a caller that takes the first one.
+    let token = CancellationToken::new();
+    let start = std::time::Instant::now();
+    let mut listener = (RecordingListener::default(), Canceller(token.clone()));
+    let solution = solve_sdvrp_with_listener(&config, &instance, &mut listener).unwrap();
+    assert!(token.is_cancelled());
+    assert!(
+        start.elapsed().as_secs_f64() < 10.0,
+        "solve was not cancelled"
+    );
+    assert!(solution.violations(&instance).is_empty());
+    assert_eq!(listener.0.end, Some((solution.clone(), solution.objective)));
+}
+
+#[test]
+fn test_initial_solution() {
+    let instance =
+        Instance::from_coord_list(10, vec![10, 10, 10], vec![(0, 0), (3, 4), (0, 5), (-4, 3)]);
+    let config = config();
+    // every customer needs a vehicle of its own, so the plan is optimal and kept on a tie.
+    let plan = vec![vec![(3, 10)], vec![(1, 10)], vec![(2, 10)]];
+    let solution = solve_sdvrp_with_initial_solution(&config, &instance, &plan, &mut ()).unwrap();
+    assert_eq!(solution.to_routes(), plan);
+    assert_eq!(solution.objective, 30);
+
+    // the plan is returned even if the search is stopped before it finds a solution.
+    let mut token = CancellationToken::new();
+    token.cancel();
+    let solution =
+        solve_sdvrp_with_initial_solution(&config, &instance, &plan, &mut token).unwrap();
+    assert_eq!(solution.to_routes(), plan);
+
+    // a worse plan is replaced by the solution of the search.
+    let plan = vec![vec![(1, 5), (2, 5)], vec![(1, 5), (2, 5)], vec![(3, 10)]];
+    let solution = solve_sdvrp_with_initial_solution(&config, &instance, &plan, &mut ()).unwrap();
+    assert_eq!(solution.objective, 30);
+
+    assert_eq!(
+        solve_sdvrp_with_initial_solution(&config, &instance, &[vec![(1, 10), (2, 10)]], &mut ())
+            .err(),
+        Some(SolveError::InvalidInitialSolution(vec![
+            Violation::CapacityExceeded {
+                route: 0,
+                load: 20,
+                capacity: 10
+            },
+            Violation::UnderDelivered {
+                customer: 3,
+                delivered: 0,
+                demand: 10
+            },
+        ]))
+    );
+}
+
+#[test]
+fn test_termination() {
+    let instance = Instance::from_coord_list(
+        10,
+        vec![6, 8, 4, 7],
+        vec![(0, 0), (3, 4), (6, 8), (0, 5), (-4, 3)],
+    );
+    for termination in [
+        config::Termination {
+            max_iterations: Some(1000),
+            ..Default::default()
+        },
+        config::Termination {
+            max_stagnation: Some(1000),
+            ..Default::default()
+        },
+        config::Termination {
+            max_iterations: Some(1_000_000_000),
+            target_objective: Some(i32::MAX),
+            ..Default::default()
+        },
+    ] {
+        let config = config::Config {
+            time_limit: f64::INFINITY,
+            termination,
+            ..Default::default()
+        };
+        let solution = solve_sdvrp(&config, &instance).unwrap();
+        assert!(solution.violations(&instance).is_empty());
+    }
+    let config = config::Config {
+        time_limit: f64::INFINITY,
+        termination: config::Termination {
+            target_objective: Some(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(matches!(
+        solve_sdvrp(&config, &instance),
+        Err(SolveError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_deterministic() {
+    let demands = (1..=30).map(|i| i * 7 % 40 + 10).collect::<Vec<_>>();
+    let coords = (0..=30)
+        .map(|i| (i * 37 % 101 - 50, i * 53 % 97 - 48))
+        .collect::<Vec<_>>();
+    let instance = Instance::from_coord_list(100, demands, coords);
+    let config = config::Config::deterministic(2000);
+    let solution = solve_sdvrp(&config, &instance).unwrap();
+    assert!(solution.violations(&instance).is_empty());
+    for _ in 0..2 {
+        assert_eq!(solve_sdvrp(&config, &instance).unwrap(), solution);
+    }
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "serde")]
+
+use sdvrp::{
+    Solution,
+    config::{AcceptanceRuleType, Config, RuinMethodType, Termination},
+    instance::Instance,
+};
+
+#[test]
+fn test_config_round_trip() {
+    let config = Config::default();
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(
+        json["acceptance"],
+        serde_json::json!({"LAHC": {"length": 83}})
+    );
+    assert_eq!(
+        json["ruin_method"],
+        serde_json::json!({"SISRs": {
+            "average_customers": 36,
+            "max_length": 8,
+            "split_rate": 0.740,
+            "preserved_probability": 0.096,
+        }})
+    );
+    assert_eq!(json["inter_operators"][0], "Relocate");
+    assert_eq!(serde_json::from_value::<Config>(json).unwrap(), config);
+
+    let config = Config {
+        acceptance_rule_type: AcceptanceRuleType::SA(100.0, 0.99),
+        ruin_method_type: RuinMethodType::Random(vec![5, 10]),
+        termination: Termination {
+            max_iterations: Some(1000),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+}
+
+#[test]
+fn test_partial_config() {
+    let config: Config = serde_json::from_str(
+        r#"{"time_limit": 1.5, "acceptance": "HCWE", "termination": {"max_stagnation": 100}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        Config {
+            time_limit: 1.5,
+            acceptance_rule_type: AcceptanceRuleType::HCWE,
+            termination: Termination {
+                max_stagnation: Some(100),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_instance_round_trip() {
+    let instance = Instance::from_coord_list(10, vec![6, 8], vec![(0, 0), (3, 4), (6, 8)]);
+    let json = serde_json::to_value(&instance).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "capacity": 10,
+            "demands": [6, 8],
+            "input": {"Planar": {
+                "coords": [[0.0, 0.0], [3.0, 4.0], [6.0, 8.0]],
+                "rounding": "Round",
+            }},
+        })
+    );
+    assert_eq!(serde_json::from_value::<Instance>(json).unwrap(), instance);
+    assert_eq!(
+        serde_json::from_str::<Instance>(
+            r#"{"capacity": 10, "demands": [6, 8], "input": {"Planar": {"coords": [[0, 0], [3, 4], [6, 8]]}}}"#
+        )
+        .unwrap(),
+        instance
+    );
+    assert!(
+        serde_json::from_str::<Instance>(
+            r#"{"capacity": 10, "demands": [6], "input": {"DenseMatrix": [[1, 1], [1, 0]]}}"#
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_solution_round_trip() {
+    let instance = Instance::from_coord_list(10, vec![6, 8], vec![(0, 0), (3, 4), (6, 8)]);
+    let solution = Solution::new(&instance, vec![vec![(1, 6), (2, 4)], vec![(2, 4)]], 40);
+    let json = serde_json::to_string(&solution).unwrap();
+    assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), solution);
+
+    // solutions written before routes had a schedule.
+    let solution = serde_json::from_str::<Solution>(
+        r#"{"routes": [{"visits": [[1, 6]], "distance": 10, "load": 6, "peak_load": 6}], "objective": 10}"#,
+    )
+    .unwrap();
+    assert_eq!(solution.routes[0].duration, 0);
+    assert!(solution.routes[0].arrival_times.is_empty());
+}
@@ -0,0 +1,51 @@
+use sdvrp::{ParseError, Solution, instance::Instance};
+
+fn example() -> Instance {
+    Instance::from_coord_list(10, vec![6, 8, 4], vec![(0, 0), (3, 4), (6, 8), (0, 5)])
+}
+
+#[test]
+fn test_sol_round_trip() {
+    let instance = example();
+    let solution = Solution::new(
+        &instance,
+        vec![vec![(1, 6), (2, 4)], vec![(2, 4), (3, 4)]],
+        42,
+    );
+    let sol = solution.to_sol_string(&instance);
+    assert_eq!(sol, "Route #1: 1 2(4)\nRoute #2: 2(4) 3\nCost 42\n");
+    assert_eq!(Solution::from_sol_str(&instance, &sol).unwrap(), solution);
+    assert!(solution.violations(&instance).is_empty());
+}
+
+#[test]
+fn test_read_cvrplib_sol() {
+    let instance = example();
+    let solution = Solution::from_sol_str(&instance, "Route #1: 1\nRoute #2: 3 2\n").unwrap();
+    assert_eq!(
+        solution.to_routes(),
+        vec![vec![(1, 6)], vec![(3, 4), (2, 8)]]
+    );
+    assert_eq!(solution.objective, solution.cost());
+    assert_eq!(
+        Solution::from_sol_str(&instance, "Route #1: 1 4\n").err(),
+        Some(ParseError::Syntax {
+            line: 1,
+            message: "invalid customer `4`".to_owned()
+        })
+    );
+}
+
+#[test]
+fn test_sol_partial_delivery() {
+    let instance = example().with_unserved_penalties(vec![1, 1, 1]).unwrap();
+    let mut solution = Solution::new(&instance, vec![vec![(1, 6), (3, 1), (2, 3), (3, 1)]], 20);
+    solution.routes[0].vehicle_type = Some(1);
+    solution.routes[0].depot = Some(0);
+    let sol = solution.to_sol_string(&instance);
+    assert_eq!(
+        sol,
+        "Route #1 [vehicle type 1, depot 0]: 1 3(1) 2(3) 3(1)\nUnserved 2(5) 3(2)\nCost 20\n"
+    );
+    assert_eq!(Solution::from_sol_str(&instance, &sol).unwrap(), solution);
+}
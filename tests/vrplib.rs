@@ -0,0 +1,115 @@
+use sdvrp::{
+    InstanceError, ParseError,
+    instance::{AlkaidInstance, Instance},
+};
+
+#[test]
+fn test_euc_2d() {
+    let instance = Instance::from_vrp_str(
+        "NAME : toy
+COMMENT : depot listed last
+TYPE : CVRP
+DIMENSION : 4
+EDGE_WEIGHT_TYPE : EUC_2D
+CAPACITY : 10
+NODE_COORD_SECTION
+ 1 3 4
+ 2 0 4
+ 3 3.5 0.5
+ 4 0 0
+DEMAND_SECTION
+1 5
+2 6
+3 7
+4 0
+DEPOT_SECTION
+ 4
+ -1
+EOF
+",
+    )
+    .unwrap();
+    assert_eq!(instance.capacity(), 10);
+    assert_eq!(instance.demands(), &[5, 6, 7]);
+    assert_eq!(instance.distance(0, 1), 5);
+    assert_eq!(instance.distance(0, 2), 4);
+    assert_eq!(instance.distance(0, 3), 4);
+    assert_eq!(instance.distance(1, 2), 3);
+    assert_eq!(instance.distance(2, 1), 3);
+}
+
+#[test]
+fn test_other_edge_weight_types() {
+    let vrp = |edge_weight_type: &str, a: (f64, f64), b: (f64, f64)| {
+        Instance::from_vrp_str(&format!(
+            "DIMENSION: 2\nCAPACITY: 10\nEDGE_WEIGHT_TYPE: {}\nNODE_COORD_SECTION\n1 {} {}\n2 {} {}\nDEMAND_SECTION\n1 0\n2 1\nDEPOT_SECTION\n1\n-1\nEOF\n",
+            edge_weight_type, a.0, a.1, b.0, b.1
+        ))
+        .unwrap()
+        .distance(0, 1)
+    };
+    assert_eq!(vrp("CEIL_2D", (0.0, 0.0), (1.5, 2.0)), 3);
+    assert_eq!(vrp("CEIL_2D", (0.0, 0.0), (1.0, 1.0)), 2);
+    assert_eq!(vrp("ATT", (0.0, 0.0), (30.0, 40.0)), 16);
+    assert_eq!(vrp("ATT", (0.0, 0.0), (10.0, 10.0)), 5);
+    assert_eq!(vrp("GEO", (48.52, 2.20), (51.30, 0.07)), 333);
+    assert_eq!(vrp("GEO", (48.52, 2.20), (40.25, -3.42)), 1055);
+}
+
+#[test]
+fn test_explicit() {
+    let full = [[0, 3, 4, 5], [3, 0, 6, 7], [4, 6, 0, 8], [5, 7, 8, 0]];
+    for (format, weights) in [
+        ("FULL_MATRIX", "0 3 4 5\n3 0 6 7\n4 6 0 8\n5 7 8 0"),
+        ("LOWER_ROW", "3\n4 6\n5 7 8"),
+        ("UPPER_ROW", "3 4 5\n6 7\n8"),
+        ("LOWER_DIAG_ROW", "0\n3 0\n4 6 0\n5 7 8 0"),
+    ] {
+        let instance = Instance::from_vrp_str(&format!(
+            "DIMENSION : 4\nCAPACITY : 10\nEDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : {}\nEDGE_WEIGHT_SECTION\n{}\nDEMAND_SECTION\n1 0\n2 1\n3 2\n4 3\nDEPOT_SECTION\n1\n-1\nEOF\n",
+            format, weights
+        ))
+        .unwrap();
+        for (i, row) in full.iter().enumerate() {
+            for (j, &distance) in row.iter().enumerate() {
+                assert_eq!(
+                    instance.distance(i, j),
+                    distance,
+                    "{} ({}, {})",
+                    format,
+                    i,
+                    j
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vrp_errors() {
+    assert_eq!(
+        Instance::from_vrp_str("DIMENSION : 2\nEDGE_WEIGHT_TYPE : EUC_2D\n").err(),
+        Some(ParseError::Missing("CAPACITY".to_owned()))
+    );
+    assert_eq!(
+        Instance::from_vrp_str(
+            "DIMENSION : 2\nCAPACITY : 10\nEDGE_WEIGHT_TYPE : MAN_2D\nNODE_COORD_SECTION\n1 0 0\n2 1 1\nDEMAND_SECTION\n1 0\n2 1\n"
+        )
+        .err(),
+        Some(ParseError::Unsupported("EDGE_WEIGHT_TYPE MAN_2D".to_owned()))
+    );
+    assert!(matches!(
+        Instance::from_vrp_str("DIMENSION : 2\nCAPACITY : 10\nNODE_COORD_SECTION\n1 0 0\n3 1 1\n"),
+        Err(ParseError::Syntax { line: 5, .. })
+    ));
+    assert_eq!(
+        Instance::from_vrp_str(
+            "DIMENSION : 2\nCAPACITY : 10\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 1 1\nDEMAND_SECTION\n1 0\n2 0\n"
+        )
+        .err(),
+        Some(ParseError::Instance(InstanceError::NonPositiveDemand {
+            customer: 1,
+            demand: 0
+        }))
+    );
+}